@@ -0,0 +1,123 @@
+// Off-chain estimate of what the PVM contract's `call()` costs: runs the contract's
+// `run_verify` (compiled from `verify.rs` exactly as the contract includes it) over
+// many calls and reports the wall-clock time and the heap each call takes. The heap
+// is measured by replaying every allocation against the contract's `bump` arithmetic,
+// which never frees, so the figure is what one call needs of the 512 KiB arena.
+// `cargo run -p prover --release --example bench_contract -- [iterations]`;
+// `tests/bench_contract.rs` runs `run` too.
+
+#[path = "../../verifier-contract/src/bump.rs"]
+#[allow(dead_code)]
+mod bump;
+#[path = "../../verifier-contract/src/verify.rs"]
+#[allow(dead_code)]
+mod verify;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+use ark_serialize::CanonicalSerialize;
+use prover::{ProverError, build_pvm_calldata, generate_proof};
+use verify::{VerifyOptions, run_verify};
+
+/// Size of the contract's bump arena (`HEAP_SIZE` in `verifier-contract/src/main.rs`)
+pub const HEAP_SIZE: usize = 512 * 1024;
+
+const DEFAULT_ITERATIONS: usize = 20;
+
+/// What one `run_verify` call took
+#[derive(Debug, Clone, Copy)]
+pub struct CallCost {
+    pub valid: bool,
+    /// Bytes the contract's bump allocator would have handed out, alignment padding included
+    pub heap_bytes: usize,
+    pub allocations: usize,
+    pub elapsed: Duration,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Usage {
+    heap_bytes: usize,
+    allocations: usize,
+}
+
+thread_local! {
+    // `Some` while `metered` runs on this thread
+    static USAGE: Cell<Option<Usage>> = const { Cell::new(None) };
+}
+
+/// Forwards to `System`, and charges every allocation made inside `metered` to a
+/// simulated bump arena. Frees are not credited back, as on-chain.
+struct BumpMeter;
+
+unsafe impl GlobalAlloc for BumpMeter {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        USAGE.with(|usage| {
+            if let Some(Usage { heap_bytes, allocations }) = usage.get() {
+                let end = bump::bump(0, heap_bytes, layout.size(), layout.align(), usize::MAX)
+                    .map_or(usize::MAX, |start| start + layout.size());
+                usage.set(Some(Usage { heap_bytes: end, allocations: allocations + 1 }));
+            }
+        });
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static METER: BumpMeter = BumpMeter;
+
+fn metered<T>(f: impl FnOnce() -> T) -> (T, Usage) {
+    USAGE.with(|usage| usage.set(Some(Usage::default())));
+    let result = f();
+    let usage = USAGE.with(Cell::take).expect("metering was switched off mid-call");
+    (result, usage)
+}
+
+/// Verify one proof of 3 * 4 = 12 `iterations` times, the way `call()` would
+pub fn run(iterations: usize) -> Result<Vec<CallCost>, ProverError> {
+    let (proof, c, pk) = generate_proof(3, 4)?;
+    let calldata = build_pvm_calldata(&proof, &c);
+    let mut vk_bytes = Vec::new();
+    pk.vk.serialize_uncompressed(&mut vk_bytes)?;
+
+    let costs = (0..iterations)
+        .map(|_| {
+            let start = Instant::now();
+            let (valid, usage) = metered(|| run_verify(&vk_bytes, &calldata, VerifyOptions::default()));
+            CallCost { valid, heap_bytes: usage.heap_bytes, allocations: usage.allocations, elapsed: start.elapsed() }
+        })
+        .collect();
+    Ok(costs)
+}
+
+fn main() -> Result<(), ProverError> {
+    let iterations = match std::env::args().nth(1) {
+        Some(arg) => arg
+            .parse()
+            .map_err(|_| ProverError::InvalidConfig(format!("iteration count {arg:?} is not a number")))?,
+        None => DEFAULT_ITERATIONS,
+    };
+    let costs = run(iterations)?;
+    let Some(first) = costs.first() else {
+        println!("⚠️ No calls run");
+        return Ok(());
+    };
+
+    let times = costs.iter().map(|cost| cost.elapsed);
+    let (min, max) = (times.clone().min().unwrap_or_default(), times.clone().max().unwrap_or_default());
+    let mean = times.sum::<Duration>() / costs.len() as u32;
+    let heap = costs.iter().map(|cost| cost.heap_bytes).max().unwrap_or_default();
+
+    println!("✅ Calls verifying: {} of {}", costs.iter().filter(|cost| cost.valid).count(), costs.len());
+    println!("⏱️ Wall-clock per call: mean {mean:?}, min {min:?}, max {max:?}");
+    println!("🧮 Heap per call: {heap} of {HEAP_SIZE} bytes in {} allocations", first.allocations);
+    if heap > HEAP_SIZE {
+        println!("❌ A call overflows the contract's bump arena and would trap on-chain");
+    }
+    Ok(())
+}
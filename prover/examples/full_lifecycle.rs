@@ -0,0 +1,53 @@
+// End-to-end walkthrough of the library API, with no CLI involved:
+// setup and proving, saving and reloading every artefact, the Foundry test
+// fixture calling the PVM verifier through its Solidity wrapper, and PVM calldata
+// checked with `verify_calldata`.
+// `cargo run -p prover --example full_lifecycle`; `tests/full_lifecycle.rs` runs `run` too.
+
+use std::path::Path;
+
+use prover::calldata::verify_calldata;
+use prover::solidity::generate_solidity_test;
+use prover::utils::{
+    CalldataMode, load_proof, load_proving_key, load_public_input, load_verifying_key, save_calldata, save_proof,
+    save_proving_key, save_public_input, save_verifying_key,
+};
+use prover::{ProverError, generate_proof, verify_proof};
+
+/// Run the whole lifecycle for 3 * 4 = 12 under `out_dir`; returns whether the calldata verifies
+pub fn run(out_dir: &Path) -> Result<bool, ProverError> {
+    // Setup and proving
+    let (proof, c, pk) = generate_proof(3, 4)?;
+
+    // Save every artefact, then continue only with what is read back
+    let pk_path = save_proving_key(&pk, out_dir)?;
+    let vk_path = save_verifying_key(&pk.vk, out_dir)?;
+    let proof_path = save_proof(&proof, out_dir)?;
+    let input_path = save_public_input(&c, out_dir)?;
+
+    let pk = load_proving_key(&pk_path)?;
+    let vk = load_verifying_key(&vk_path)?;
+    let proof = load_proof(&proof_path)?;
+    let c = load_public_input(&input_path)?;
+    assert_eq!(vk, pk.vk, "reloaded keys disagree");
+
+    if !verify_proof(&proof, c, &vk)? {
+        return Ok(false);
+    }
+
+    // Foundry fixture calling the deployed PVM verifier through `VerifyFromSolidity`
+    generate_solidity_test(&proof, &c, &out_dir.join("Verifier.t.sol"))?;
+
+    // Calldata for the PVM contract, checked off-chain the way the contract decodes it
+    let calldata_path = out_dir.join("calldata.bin");
+    save_calldata(&proof, &c, &calldata_path, CalldataMode::Pvm)?;
+    let calldata = std::fs::read(&calldata_path)?;
+    verify_calldata(&calldata, &vk)
+}
+
+fn main() -> Result<(), ProverError> {
+    let out_dir = std::env::temp_dir().join("poof_full_lifecycle");
+    let valid = run(&out_dir)?;
+    println!("✅ Calldata verification result: {valid}");
+    Ok(())
+}
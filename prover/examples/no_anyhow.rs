@@ -0,0 +1,11 @@
+// Compile check: the prover library is usable with nothing but its own `ProverError`.
+// This example deliberately has no `anyhow` (and no `Box<dyn Error>`) anywhere.
+
+use prover::{ProverError, generate_proof, verify_proof};
+
+fn main() -> Result<(), ProverError> {
+    let (proof, c, pk) = generate_proof(3, 4)?;
+    let valid = verify_proof(&proof, c, &pk.vk)?;
+    println!("✅ Verification result: {valid}");
+    Ok(())
+}
@@ -0,0 +1,28 @@
+// A proof together with everything needed to check it.
+// `ProofBundle` serializes as one unit, so a proof, its public input and the
+// verifying key can be stored or sent as a single artefact
+// (see `utils::save_bundle` / `utils::load_bundle`).
+
+use ark_bn254::{Bn254, Fr};
+use ark_groth16::{Proof, VerifyingKey};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use crate::error::ProverError;
+
+#[derive(Debug, Clone, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ProofBundle {
+    pub proof: Proof<Bn254>,
+    pub public_input: Fr,
+    pub vk: VerifyingKey<Bn254>,
+}
+
+impl ProofBundle {
+    pub fn new(proof: Proof<Bn254>, public_input: Fr, vk: VerifyingKey<Bn254>) -> Self {
+        Self { proof, public_input, vk }
+    }
+
+    /// Check the bundled proof against its own public input and verifying key
+    pub fn verify(&self) -> Result<bool, ProverError> {
+        crate::verify_proof(&self.proof, self.public_input, &self.vk)
+    }
+}
@@ -0,0 +1,127 @@
+// Verification results remembered per (proof, public inputs), for relayers that see the
+// same proof more than once. `VerifierCache` prepares the verifying key once and keeps
+// up to `capacity` results, evicting the least recently used. Entries are keyed by
+// Keccak256 of the compressed proof followed by the compressed public inputs.
+// Errors (e.g. an input count the key does not expect) are returned, not cached.
+
+use ark_bn254::{Bn254, Fr};
+use ark_groth16::{Groth16, PreparedVerifyingKey, Proof, VerifyingKey, prepare_verifying_key};
+use ark_serialize::CanonicalSerialize;
+use sha3::{Digest, Keccak256};
+use std::collections::{HashMap, VecDeque};
+
+use crate::error::ProverError;
+
+pub struct VerifierCache {
+    pvk: PreparedVerifyingKey<Bn254>,
+    capacity: usize,
+    results: HashMap<[u8; 32], bool>,
+    /// Keys from least to most recently used
+    order: VecDeque<[u8; 32]>,
+    hits: usize,
+}
+
+impl VerifierCache {
+    /// A cache of at most `capacity` results for proofs under `vk`; 0 disables caching
+    pub fn new(vk: &VerifyingKey<Bn254>, capacity: usize) -> Self {
+        VerifierCache {
+            pvk: prepare_verifying_key(vk),
+            capacity,
+            results: HashMap::with_capacity(capacity),
+            order: VecDeque::with_capacity(capacity),
+            hits: 0,
+        }
+    }
+
+    /// Verify `proof` against `inputs`, answering from the cache when this pair was seen before
+    pub fn verify(&mut self, proof: &Proof<Bn254>, inputs: &[Fr]) -> Result<bool, ProverError> {
+        let key = cache_key(proof, inputs);
+        if let Some(&valid) = self.results.get(&key) {
+            self.hits += 1;
+            self.touch(key);
+            return Ok(valid);
+        }
+
+        let valid = crate::proof_points_are_valid(proof)
+            && Groth16::<Bn254>::verify_proof(&self.pvk, proof, inputs).map_err(ProverError::Verification)?;
+        self.insert(key, valid);
+        Ok(valid)
+    }
+
+    /// Number of `verify` calls answered from the cache
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+
+    // Move `key` to the most recently used end; linear in the capacity, which stays small
+    fn touch(&mut self, key: [u8; 32]) {
+        if let Some(position) = self.order.iter().position(|k| *k == key) {
+            self.order.remove(position);
+        }
+        self.order.push_back(key);
+    }
+
+    fn insert(&mut self, key: [u8; 32], valid: bool) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.results.len() == self.capacity
+            && let Some(oldest) = self.order.pop_front()
+        {
+            self.results.remove(&oldest);
+        }
+        self.results.insert(key, valid);
+        self.order.push_back(key);
+    }
+}
+
+fn cache_key(proof: &Proof<Bn254>, inputs: &[Fr]) -> [u8; 32] {
+    let mut bytes = Vec::new();
+    proof.serialize_compressed(&mut bytes).expect("serializing into a Vec cannot fail");
+    for input in inputs {
+        input.serialize_compressed(&mut bytes).expect("serializing into a Vec cannot fail");
+    }
+    Keccak256::digest(&bytes).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_proof, prove_with_key};
+
+    #[test]
+    fn test_repeated_proof_hits_the_cache_and_distinct_ones_do_not() {
+        let (proof, c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let (other, other_c) = prove_with_key(&pk, 5, 6, false).expect("proof generation failed");
+        let mut cache = VerifierCache::new(&pk.vk, 2);
+
+        assert!(cache.verify(&proof, &[c]).unwrap());
+        assert!(cache.verify(&proof, &[c]).unwrap());
+        assert_eq!(cache.hits(), 1);
+
+        assert!(cache.verify(&other, &[other_c]).unwrap());
+        assert!(!cache.verify(&proof, &[other_c]).unwrap());
+        assert_eq!(cache.hits(), 1);
+
+        // Bounded: the third distinct entry evicted the least recently used (proof, c)
+        assert_eq!(cache.len(), 2);
+        assert!(cache.verify(&proof, &[c]).unwrap());
+        assert_eq!(cache.hits(), 1);
+    }
+
+    #[test]
+    fn test_key_is_keccak_of_proof_then_input_bytes() {
+        let (proof, c, _pk) = generate_proof(3, 4).expect("proof generation failed");
+        let mut bytes = crate::utils::encode_proof(&proof, crate::utils::ProofEncoding::Compressed);
+        c.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(cache_key(&proof, &[c]), <[u8; 32]>::from(Keccak256::digest(&bytes)));
+    }
+}
@@ -0,0 +1,346 @@
+// Decoding and checking of the PVM calldata produced by `utils::build_calldata`.
+// Layout (164 bytes):
+// - 4 bytes: selector
+// - 128 bytes: compressed Groth16 proof (A: 32, B: 64, C: 32)
+// - 32 bytes: public input, big-endian
+// `calldata_payload` and `extract_public_input_from_calldata` also accept the
+// `Raw` and `SolidityAbi` framings, for debugging calldata of any mode.
+// Circuits with N public inputs append N such words (`build_calldata_with_inputs`);
+// `verify_calldata` reads N from the verifying key; `verify_calldata_hex` takes `0x` hex text.
+
+use ark_bn254::{Bn254, Fr};
+use ark_groth16::{Groth16, Proof, VerifyingKey, prepare_verifying_key};
+use ark_serialize::CanonicalDeserialize;
+
+use crate::error::ProverError;
+use crate::ethereum::{VkByteOrder, vk_bytes_to_arkworks};
+use crate::utils::CalldataMode;
+
+pub const SELECTOR_LEN: usize = 4;
+pub const PROOF_LEN: usize = 128;
+pub const INPUT_LEN: usize = 32;
+pub const PAYLOAD_LEN: usize = PROOF_LEN + INPUT_LEN;
+pub const CALLDATA_LEN: usize = SELECTOR_LEN + PAYLOAD_LEN;
+
+// Selector, then the offset and length words in front of an ABI `bytes` argument
+const ABI_HEADER_LEN: usize = SELECTOR_LEN + 64;
+
+// Offset of the compressed G2 point B inside the proof bytes
+const G2_OFFSET: usize = 32;
+const G2_LEN: usize = 64;
+
+/// Byte order of the two Fq components of the proof's G2 point
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum G2Ordering {
+    /// arkworks order: [c0, c1]
+    Arkworks,
+    /// Ethereum order: [c1, c0]
+    Swapped,
+}
+
+/// Result of checking the proof under one G2 ordering
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct OrderingCheck {
+    /// A, B and C decode to points on the curve and in the right subgroup
+    pub on_curve: bool,
+    /// The decoded proof verifies against the verifying key
+    pub verifies: bool,
+}
+
+/// What `diagnose_calldata` found out about a piece of calldata
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CalldataDiagnosis {
+    pub length_ok: bool,
+    pub input_decodes: bool,
+    pub arkworks: OrderingCheck,
+    pub swapped: OrderingCheck,
+}
+
+impl CalldataDiagnosis {
+    /// The G2 ordering under which the proof verifies, if any
+    pub fn working_ordering(&self) -> Option<G2Ordering> {
+        if self.arkworks.verifies {
+            Some(G2Ordering::Arkworks)
+        } else if self.swapped.verifies {
+            Some(G2Ordering::Swapped)
+        } else {
+            None
+        }
+    }
+}
+
+/// Decode calldata into its proof and public input
+pub fn decode_calldata(calldata: &[u8]) -> Result<(Proof<Bn254>, Fr), ProverError> {
+    let (proof, inputs) = decode_calldata_with_inputs(calldata, 1)?;
+    Ok((proof, inputs[0]))
+}
+
+/// Decode calldata carrying `num_inputs` public inputs after the proof
+pub fn decode_calldata_with_inputs(calldata: &[u8], num_inputs: usize) -> Result<(Proof<Bn254>, Vec<Fr>), ProverError> {
+    let expected = SELECTOR_LEN + PROOF_LEN + INPUT_LEN * num_inputs;
+    if calldata.len() != expected {
+        return Err(ProverError::InvalidCalldata(format!("expected {} bytes, got {}", expected, calldata.len())));
+    }
+    let proof_bytes = &calldata[SELECTOR_LEN..SELECTOR_LEN + PROOF_LEN];
+    let proof = Proof::<Bn254>::deserialize_compressed(proof_bytes)?;
+    let inputs = calldata[SELECTOR_LEN + PROOF_LEN..]
+        .chunks(INPUT_LEN)
+        .map(decode_public_input)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((proof, inputs))
+}
+
+/// The proof-and-input payload of calldata in any `CalldataMode` framing, told apart by length
+pub fn calldata_payload(calldata: &[u8]) -> Result<(&[u8], CalldataMode), ProverError> {
+    match calldata.len() {
+        PAYLOAD_LEN => Ok((calldata, CalldataMode::Raw)),
+        CALLDATA_LEN => Ok((&calldata[SELECTOR_LEN..], CalldataMode::Pvm)),
+        len if len >= ABI_HEADER_LEN => {
+            let offset = abi_word(&calldata[SELECTOR_LEN..SELECTOR_LEN + 32]);
+            let length = abi_word(&calldata[SELECTOR_LEN + 32..ABI_HEADER_LEN]);
+            let padded = PAYLOAD_LEN.div_ceil(32) * 32;
+            if offset != Some(32) || length != Some(PAYLOAD_LEN) || len != ABI_HEADER_LEN + padded {
+                return Err(ProverError::InvalidCalldata(format!(
+                    "{} bytes do not hold a {}-byte ABI-encoded payload",
+                    len, PAYLOAD_LEN
+                )));
+            }
+            Ok((&calldata[ABI_HEADER_LEN..ABI_HEADER_LEN + PAYLOAD_LEN], CalldataMode::SolidityAbi))
+        }
+        len => Err(ProverError::InvalidCalldata(format!(
+            "expected {} (raw), {} (PVM) or ABI-encoded calldata, got {} bytes",
+            PAYLOAD_LEN, CALLDATA_LEN, len
+        ))),
+    }
+}
+
+/// The public input calldata encodes: the big-endian word after the proof in its payload
+pub fn extract_public_input_from_calldata(calldata: &[u8]) -> Result<Fr, ProverError> {
+    let (payload, _) = calldata_payload(calldata)?;
+    decode_public_input(&payload[PROOF_LEN..])
+}
+
+/// Verify calldata against a verifying key, expecting as many public inputs as the key has
+pub fn verify_calldata(calldata: &[u8], vk: &VerifyingKey<Bn254>) -> Result<bool, ProverError> {
+    let num_inputs = vk.gamma_abc_g1.len().saturating_sub(1);
+    let (proof, inputs) = decode_calldata_with_inputs(calldata, num_inputs)?;
+    let pvk = prepare_verifying_key(vk);
+    Ok(Groth16::<Bn254>::verify_proof(&pvk, &proof, &inputs)?)
+}
+
+/// `verify_calldata` for calldata pasted as hex, e.g. from a transaction or `save_calldata`'s log.
+/// Surrounding whitespace and a `0x` prefix are ignored.
+pub fn verify_calldata_hex(hex: &str, vk: &VerifyingKey<Bn254>) -> Result<bool, ProverError> {
+    let hex = hex.trim();
+    let digits = hex.strip_prefix("0x").unwrap_or(hex);
+    verify_calldata(&decode_hex(digits)?, vk)
+}
+
+/// Verify calldata against serialized verifying key bytes in either layout.
+/// Ethereum-ordered bytes are converted to arkworks order before deserializing,
+/// the form the PVM contract's `run_verify` expects.
+pub fn verify_calldata_with_vk_bytes(calldata: &[u8], vk_bytes: &[u8], order: VkByteOrder) -> Result<bool, ProverError> {
+    let arkworks = vk_bytes_to_arkworks(vk_bytes, order)?;
+    let vk = VerifyingKey::<Bn254>::deserialize_uncompressed(&arkworks[..])?;
+    verify_calldata(calldata, &vk)
+}
+
+/// Try the calldata under both G2 orderings and report which (if any) verifies
+pub fn diagnose_calldata(calldata: &[u8], vk: &VerifyingKey<Bn254>) -> CalldataDiagnosis {
+    let mut diagnosis = CalldataDiagnosis {
+        length_ok: calldata.len() == CALLDATA_LEN,
+        input_decodes: false,
+        arkworks: OrderingCheck::default(),
+        swapped: OrderingCheck::default(),
+    };
+    if !diagnosis.length_ok {
+        return diagnosis;
+    }
+
+    let input = decode_public_input(&calldata[SELECTOR_LEN + PROOF_LEN..]).ok();
+    diagnosis.input_decodes = input.is_some();
+
+    let proof_bytes = &calldata[SELECTOR_LEN..SELECTOR_LEN + PROOF_LEN];
+    diagnosis.arkworks = check_ordering(proof_bytes, input, vk);
+
+    let mut swapped = proof_bytes.to_vec();
+    swap_g2_components(&mut swapped[G2_OFFSET..G2_OFFSET + G2_LEN]);
+    diagnosis.swapped = check_ordering(&swapped, input, vk);
+
+    diagnosis
+}
+
+/// Swap the c0/c1 halves of a compressed G2 point, keeping the flag bits in the last byte
+pub fn swap_g2_components(point: &mut [u8]) {
+    assert_eq!(point.len(), G2_LEN);
+    let flags = point[G2_LEN - 1] & 0b1100_0000;
+    point[G2_LEN - 1] &= 0b0011_1111;
+    let (lo, hi) = point.split_at_mut(G2_LEN / 2);
+    lo.swap_with_slice(hi);
+    point[G2_LEN - 1] |= flags;
+}
+
+fn check_ordering(proof_bytes: &[u8], input: Option<Fr>, vk: &VerifyingKey<Bn254>) -> OrderingCheck {
+    let proof = match Proof::<Bn254>::deserialize_compressed_unchecked(proof_bytes) {
+        Ok(p) => p,
+        Err(_) => return OrderingCheck::default(),
+    };
+    let on_curve = proof.a.is_on_curve()
+        && proof.a.is_in_correct_subgroup_assuming_on_curve()
+        && proof.b.is_on_curve()
+        && proof.b.is_in_correct_subgroup_assuming_on_curve()
+        && proof.c.is_on_curve()
+        && proof.c.is_in_correct_subgroup_assuming_on_curve();
+
+    let verifies = match input {
+        Some(input) if on_curve => {
+            let pvk = prepare_verifying_key(vk);
+            Groth16::<Bn254>::verify_proof(&pvk, &proof, &[input]).unwrap_or(false)
+        }
+        _ => false,
+    };
+
+    OrderingCheck { on_curve, verifies }
+}
+
+// Big-endian 32-byte ABI word -> usize, if it fits
+fn abi_word(word: &[u8]) -> Option<usize> {
+    let (high, low) = word.split_at(24);
+    if high.iter().any(|&b| b != 0) {
+        return None;
+    }
+    usize::try_from(u64::from_be_bytes(low.try_into().ok()?)).ok()
+}
+
+fn decode_hex(digits: &str) -> Result<Vec<u8>, ProverError> {
+    if !digits.len().is_multiple_of(2) {
+        return Err(ProverError::InvalidCalldata(format!("odd number of hex digits ({})", digits.len())));
+    }
+    if let Some((i, c)) = digits.char_indices().find(|(_, c)| !c.is_ascii_hexdigit()) {
+        return Err(ProverError::InvalidCalldata(format!("non-hex character {:?} at position {}", c, i)));
+    }
+    Ok((0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).expect("checked hex digits"))
+        .collect())
+}
+
+// Big-endian 32-byte word -> Fr, rejecting values >= the modulus
+fn decode_public_input(bytes: &[u8]) -> Result<Fr, ProverError> {
+    let mut le = bytes.to_vec();
+    le.reverse();
+    Ok(Fr::deserialize_uncompressed(&le[..])?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate_proof;
+    use crate::utils::build_calldata;
+
+    #[test]
+    fn test_correct_calldata_verifies_in_arkworks_order() {
+        let (proof, c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let calldata = build_calldata(&proof, &c, CalldataMode::Pvm).expect("calldata build failed");
+
+        assert!(verify_calldata(&calldata, &pk.vk).expect("verification failed"));
+        let diagnosis = diagnose_calldata(&calldata, &pk.vk);
+        assert!(diagnosis.arkworks.on_curve);
+        assert_eq!(diagnosis.working_ordering(), Some(G2Ordering::Arkworks));
+    }
+
+    #[test]
+    fn test_diagnosis_detects_swapped_g2_ordering() {
+        let (proof, c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let mut calldata = build_calldata(&proof, &c, CalldataMode::Pvm).expect("calldata build failed");
+        let start = SELECTOR_LEN + G2_OFFSET;
+        swap_g2_components(&mut calldata[start..start + G2_LEN]);
+
+        assert!(verify_calldata(&calldata, &pk.vk).map(|ok| !ok).unwrap_or(true));
+        let diagnosis = diagnose_calldata(&calldata, &pk.vk);
+        assert!(diagnosis.input_decodes);
+        assert!(!diagnosis.arkworks.verifies);
+        assert!(diagnosis.swapped.on_curve);
+        assert_eq!(diagnosis.working_ordering(), Some(G2Ordering::Swapped));
+    }
+
+    #[test]
+    fn test_extracted_input_matches_built_calldata_in_every_mode() {
+        let (proof, c, _pk) = generate_proof(3, 4).expect("proof generation failed");
+        for mode in [CalldataMode::Pvm, CalldataMode::Raw, CalldataMode::SolidityAbi] {
+            let calldata = build_calldata(&proof, &c, mode).expect("calldata build failed");
+            assert_eq!(extract_public_input_from_calldata(&calldata).expect("extraction failed"), c);
+            assert_eq!(calldata_payload(&calldata).expect("framing not detected").1, mode);
+        }
+        assert!(extract_public_input_from_calldata(&[0u8; 10]).is_err());
+    }
+
+    #[test]
+    fn test_ethereum_ordered_vk_bytes_verify_with_the_flag() {
+        use crate::ethereum::vk_to_ethereum_bytes;
+
+        let (proof, c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let calldata = build_calldata(&proof, &c, CalldataMode::Pvm).expect("calldata build failed");
+        let eth_bytes = vk_to_ethereum_bytes(&pk.vk);
+
+        let verified = verify_calldata_with_vk_bytes(&calldata, &eth_bytes, VkByteOrder::Ethereum);
+        assert!(verified.expect("verification failed"));
+        // Without the flag the same bytes do not even decode as an arkworks key
+        assert!(verify_calldata_with_vk_bytes(&calldata, &eth_bytes, VkByteOrder::Arkworks).is_err());
+    }
+
+    #[test]
+    fn test_two_public_input_calldata_verifies() {
+        use crate::circuit::{PublicInputsCircuit, RootRelation};
+        use crate::utils::build_calldata_with_inputs;
+        use ark_snark::SNARK;
+
+        // 6 - 3x = 0 has the root x = 2
+        let coeffs = [Fr::from(6u64), -Fr::from(3u64)];
+        let mut rng = rand::thread_rng();
+        let (pk, vk) = Groth16::<Bn254>::circuit_specific_setup(
+            PublicInputsCircuit::setup_shape(2, RootRelation { x: None }),
+            &mut rng,
+        )
+        .expect("setup failed");
+        let instance = PublicInputsCircuit::instance(&coeffs, RootRelation { x: Some(Fr::from(2u64)) });
+        let proof = Groth16::<Bn254>::prove(&pk, instance, &mut rng).expect("proving failed");
+
+        let calldata = build_calldata_with_inputs(&proof, &coeffs, CalldataMode::Pvm).expect("calldata build failed");
+        assert_eq!(calldata.len(), SELECTOR_LEN + PROOF_LEN + 2 * INPUT_LEN);
+        assert!(verify_calldata(&calldata, &vk).expect("verification failed"));
+
+        let swapped = build_calldata_with_inputs(&proof, &[coeffs[1], coeffs[0]], CalldataMode::Pvm).unwrap();
+        assert!(!verify_calldata(&swapped, &vk).expect("verification failed"));
+        // A single-input key expects the 164-byte layout
+        let (_proof, _c, mul_pk) = generate_proof(3, 4).expect("proof generation failed");
+        assert!(verify_calldata(&calldata, &mul_pk.vk).is_err());
+    }
+
+    #[test]
+    fn test_calldata_hex_from_the_save_log_verifies() {
+        use crate::utils::hex_string;
+
+        let (proof, c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let calldata = build_calldata(&proof, &c, CalldataMode::Pvm).expect("calldata build failed");
+        // The form `save_calldata` logs
+        let hex = format!("0x{}", hex_string(&calldata));
+
+        assert!(verify_calldata_hex(&hex, &pk.vk).expect("verification failed"));
+        assert!(verify_calldata_hex(&format!(" {}\n", &hex[2..].to_uppercase()), &pk.vk).expect("verification failed"));
+
+        let odd = verify_calldata_hex(&hex[..hex.len() - 1], &pk.vk).expect_err("odd length should be rejected");
+        assert!(odd.to_string().contains("odd number of hex digits"));
+        let non_hex = format!("0x{}", hex[2..].replacen('0', "g", 1));
+        let bad = verify_calldata_hex(&non_hex, &pk.vk).expect_err("non-hex should be rejected");
+        assert!(bad.to_string().contains("non-hex character 'g'"));
+    }
+
+    #[test]
+    fn test_diagnosis_of_short_calldata() {
+        let (_proof, _c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let diagnosis = diagnose_calldata(&[0u8; 10], &pk.vk);
+        assert!(!diagnosis.length_ok);
+        assert_eq!(diagnosis.working_ordering(), None);
+    }
+}
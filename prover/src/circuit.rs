@@ -1,18 +1,78 @@
-// Defines the zkSNARK constraint system for a * b = c.
-// This struct holds optional private inputs a and b, and public output c.
-// Implements the ConstraintSynthesizer trait to add constraints to the circuit.
+// Defines the zkSNARK constraint systems used by the prover.
+// - `MulCircuit`: private inputs a and b, public output c, enforcing a * b = c
+//   (optionally also a != 0 and b != 0). `MulInputs` is its serde-friendly witness.
+// - `SumCircuit`: private inputs xs, public output total, enforcing sum(xs) = total.
+// - `WeightedSumCircuit`: public weights ws, private xs, public total, enforcing sum(ws[i] * xs[i]) = total.
+// - `FactorCircuit`: private factors p, q > 1, public output n, enforcing p * q = n.
+// - `ModCircuit`: public x, modulus m and remainder r, private quotient q, enforcing x = q * m + r with 0 <= r < m.
+// - `PowerCircuit`: public base and result, private exponent < 2^max_bits, enforcing base^exponent = result.
+// - `RangeWithBitCircuit`: private value < 2^num_bits, revealing one chosen bit or its bit length.
+// - `ParityCircuit`: private value < 2^num_bits, public output its least-significant bit.
+// - `PoseidonPreimageCircuit`: private secret, public hash, enforcing poseidon_sponge_hash([secret]) = hash.
+// - `PoseidonHashOutputCircuit`: private secret, public output its hash as computed in-circuit.
+// - `PoseidonSignatureCircuit`: private secret key and nonce behind a public key and a
+//   signature (r, s) on a public message hash; see `signature` for the scheme.
+// - `NonceBound`: any of the above with an extra public `context_nonce`, against cross-context replay.
+// - `PublicInputsCircuit` / `HashedInputsCircuit`: an `InputRelation` over inputs that are either all
+//   public, or private and committed to by one public Poseidon hash (one field element of calldata).
+// Each implements the ConstraintSynthesizer trait to add constraints to the circuit.
+// `setup_shape` builds the all-`None` circuit for key generation and `instance` a fully
+// assigned one from plain values, computing the public output. `Assignment` tells the
+// two apart before proving.
+// Secret witnesses (Poseidon preimages, the private inputs behind a hash commitment) are
+// held in `Zeroizing`, which clears them when the circuit is dropped. This is best-effort:
+// synthesis copies every assignment into the constraint system and the proving key's
+// witness vectors, which arkworks drops without clearing.
 
+use ark_ff::{BigInteger, Field, PrimeField};
 use ark_r1cs_std::alloc::AllocVar;
+use ark_r1cs_std::boolean::Boolean;
+use ark_r1cs_std::R1CSVar;
 use ark_r1cs_std::fields::fp::FpVar;
 use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
 use ark_bn254::Fr;
 use ark_r1cs_std::eq::EqGadget;
+use ark_r1cs_std::fields::FieldVar;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
 
+use crate::poseidon::{poseidon_sponge_hash, poseidon_sponge_hash_gadget};
+use crate::signature::{public_key, sign_with_nonce};
 
+
+#[derive(Clone)]
 pub struct MulCircuit {
     pub a: Option<Fr>,
     pub b: Option<Fr>,
     pub c: Option<Fr>,
+    /// Also enforce a != 0 and b != 0 by requiring their inverses to exist
+    pub nonzero: bool,
+}
+
+impl MulCircuit {
+    /// Setup shape (all assignments missing, no nonzero check)
+    pub fn setup_shape() -> Self {
+        MulCircuit { a: None, b: None, c: None, nonzero: false }
+    }
+
+    /// Instance for a * b, with c computed in the field so it cannot overflow
+    pub fn instance(a: u64, b: u64) -> Self {
+        let (a, b) = (Fr::from(a), Fr::from(b));
+        MulCircuit { a: Some(a), b: Some(b), c: Some(a * b), nonzero: false }
+    }
+
+    /// Instance for `inputs.a * inputs.b`
+    pub fn from_inputs(inputs: MulInputs) -> Self {
+        MulCircuit::instance(inputs.a, inputs.b)
+    }
+}
+
+/// Plain-integer witness of a `MulCircuit`, e.g. a JSON prove request `{"a": 3, "b": 4}`.
+/// Field elements stay out of the serde surface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MulInputs {
+    pub a: u64,
+    pub b: u64,
 }
 
 impl ConstraintSynthesizer<Fr> for MulCircuit {
@@ -21,9 +81,1219 @@ impl ConstraintSynthesizer<Fr> for MulCircuit {
         let b = FpVar::new_witness(cs.clone(), || self.b.ok_or(SynthesisError::AssignmentMissing))?;
         let c = FpVar::new_input(cs.clone(), || self.c.ok_or(SynthesisError::AssignmentMissing))?;
 
+        if self.nonzero {
+            // inverse() enforces a * a_inv == 1, unsatisfiable for a == 0
+            let _a_inv = a.inverse()?;
+            let _b_inv = b.inverse()?;
+        }
+
         let ab = &a * &b;
         ab.enforce_equal(&c)?;
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+
+#[derive(Clone)]
+pub struct SumCircuit {
+    pub xs: Vec<Option<Fr>>,
+    pub total: Option<Fr>,
+}
+
+impl SumCircuit {
+    /// Setup shape for `n` summands (all assignments missing)
+    pub fn setup_shape(n: usize) -> Self {
+        SumCircuit { xs: vec![None; n], total: None }
+    }
+
+    /// Instance for the summands `xs`, with the total computed in the field
+    pub fn instance(xs: &[u64]) -> Self {
+        let xs: Vec<Fr> = xs.iter().map(|&x| Fr::from(x)).collect();
+        let total = xs.iter().sum();
+        SumCircuit { xs: xs.into_iter().map(Some).collect(), total: Some(total) }
+    }
+}
+
+impl ConstraintSynthesizer<Fr> for SumCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        let mut sum = FpVar::<Fr>::zero();
+        for x in self.xs {
+            let x = FpVar::new_witness(cs.clone(), || x.ok_or(SynthesisError::AssignmentMissing))?;
+            sum += x;
+        }
+        let total = FpVar::new_input(cs.clone(), || self.total.ok_or(SynthesisError::AssignmentMissing))?;
+
+        sum.enforce_equal(&total)?;
+
+        Ok(())
+    }
+}
+
+
+#[derive(Clone)]
+pub struct WeightedSumCircuit {
+    /// Public, allocated before `total`
+    pub weights: Vec<Option<Fr>>,
+    pub xs: Vec<Option<Fr>>,
+    /// Public: the dot product of `weights` and `xs`
+    pub total: Option<Fr>,
+}
+
+impl WeightedSumCircuit {
+    /// Setup shape for `n` weighted terms (all assignments missing)
+    pub fn setup_shape(n: usize) -> Self {
+        WeightedSumCircuit { weights: vec![None; n], xs: vec![None; n], total: None }
+    }
+
+    /// Instance for `weights` and `xs`, with the total computed in the field.
+    /// Slices of different lengths give a circuit whose synthesis fails.
+    pub fn instance(weights: &[u64], xs: &[u64]) -> Self {
+        let weights: Vec<Fr> = weights.iter().map(|&w| Fr::from(w)).collect();
+        let xs: Vec<Fr> = xs.iter().map(|&x| Fr::from(x)).collect();
+        let total = weights.iter().zip(&xs).map(|(w, x)| *w * x).sum();
+        WeightedSumCircuit {
+            weights: weights.into_iter().map(Some).collect(),
+            xs: xs.into_iter().map(Some).collect(),
+            total: Some(total),
+        }
+    }
+}
+
+impl ConstraintSynthesizer<Fr> for WeightedSumCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        // One weight per term; otherwise no assignment could mean what the caller intended
+        if self.weights.len() != self.xs.len() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let weights = self
+            .weights
+            .iter()
+            .map(|w| FpVar::new_input(cs.clone(), || w.ok_or(SynthesisError::AssignmentMissing)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let total = FpVar::new_input(cs.clone(), || self.total.ok_or(SynthesisError::AssignmentMissing))?;
+
+        // Weights are variables, not constants, so each term costs one multiplication constraint
+        let mut sum = FpVar::<Fr>::zero();
+        for (w, x) in weights.iter().zip(self.xs) {
+            let x = FpVar::new_witness(cs.clone(), || x.ok_or(SynthesisError::AssignmentMissing))?;
+            sum += w * &x;
+        }
+
+        sum.enforce_equal(&total)?;
+
+        Ok(())
+    }
+}
+
+
+/// Constrain `value` to fit in `num_bits` bits and return its little-endian bit decomposition.
+/// `num_bits` must stay below the field size so the recomposition cannot wrap around;
+/// a wider decomposition is refused with `SynthesisError::Unsatisfiable`.
+pub fn enforce_bit_decomposition(
+    cs: ConstraintSystemRef<Fr>,
+    value: &FpVar<Fr>,
+    num_bits: usize,
+) -> Result<Vec<Boolean<Fr>>, SynthesisError> {
+    if num_bits >= Fr::MODULUS_BIT_SIZE as usize {
+        return Err(SynthesisError::Unsatisfiable);
+    }
+
+    let bit_values: Option<Vec<bool>> = value.value().ok().map(|v| {
+        let repr = v.into_bigint();
+        (0..num_bits).map(|i| repr.get_bit(i)).collect()
+    });
+
+    let bits = (0..num_bits)
+        .map(|i| {
+            Boolean::new_witness(cs.clone(), || {
+                bit_values.as_ref().map(|bits| bits[i]).ok_or(SynthesisError::AssignmentMissing)
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Boolean::le_bits_to_fp_var(&bits)?.enforce_equal(value)?;
+
+    Ok(bits)
+}
+
+
+/// Bit width of `p - 2` and `q - 2` in `FactorCircuit`, enough for any u64 factor
+pub const FACTOR_BITS: usize = 64;
+
+#[derive(Clone)]
+pub struct FactorCircuit {
+    pub p: Option<Fr>,
+    pub q: Option<Fr>,
+    pub n: Option<Fr>,
+}
+
+impl FactorCircuit {
+    /// Setup shape (all assignments missing)
+    pub fn setup_shape() -> Self {
+        FactorCircuit { p: None, q: None, n: None }
+    }
+
+    /// Instance for the factors `p` and `q` of n = p * q
+    pub fn instance(p: u64, q: u64) -> Self {
+        let (p, q) = (Fr::from(p), Fr::from(q));
+        FactorCircuit { p: Some(p), q: Some(q), n: Some(p * q) }
+    }
+}
+
+impl ConstraintSynthesizer<Fr> for FactorCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        let p = FpVar::new_witness(cs.clone(), || self.p.ok_or(SynthesisError::AssignmentMissing))?;
+        let q = FpVar::new_witness(cs.clone(), || self.q.ok_or(SynthesisError::AssignmentMissing))?;
+        let n = FpVar::new_input(cs.clone(), || self.n.ok_or(SynthesisError::AssignmentMissing))?;
+
+        // p > 1 and q > 1: p - 2 and q - 2 must be small non-negative integers
+        let two = FpVar::constant(Fr::from(2u64));
+        enforce_bit_decomposition(cs.clone(), &(&p - &two), FACTOR_BITS)?;
+        enforce_bit_decomposition(cs.clone(), &(&q - &two), FACTOR_BITS)?;
+
+        (&p * &q).enforce_equal(&n)?;
+
+        Ok(())
+    }
+}
+
+
+/// Widest `ModCircuit::num_bits`: q * m + r stays below 2^(2 * num_bits + 2), which must not reach the modulus
+pub const MAX_MOD_BITS: usize = 125;
+
+#[derive(Clone)]
+pub struct ModCircuit {
+    /// Public
+    pub x: Option<Fr>,
+    /// Public
+    pub m: Option<Fr>,
+    pub q: Option<Fr>,
+    /// Public: x mod m
+    pub r: Option<Fr>,
+    /// Bit width of q, r and m - r - 1, at most `MAX_MOD_BITS`
+    pub num_bits: usize,
+}
+
+impl ModCircuit {
+    /// Setup shape for `num_bits`-bit operands (all assignments missing)
+    pub fn setup_shape(num_bits: usize) -> Self {
+        ModCircuit { x: None, m: None, q: None, r: None, num_bits }
+    }
+
+    /// Instance for x mod m. Panics if `m` is zero.
+    pub fn instance(x: u64, m: u64, num_bits: usize) -> Self {
+        let (q, r) = (x / m, x % m);
+        ModCircuit {
+            x: Some(Fr::from(x)),
+            m: Some(Fr::from(m)),
+            q: Some(Fr::from(q)),
+            r: Some(Fr::from(r)),
+            num_bits,
+        }
+    }
+}
+
+impl ConstraintSynthesizer<Fr> for ModCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        if self.num_bits > MAX_MOD_BITS {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        // With x or m private, x = r, m = r + 1, q = 0 would prove any remainder
+        let x = FpVar::new_input(cs.clone(), || self.x.ok_or(SynthesisError::AssignmentMissing))?;
+        let m = FpVar::new_input(cs.clone(), || self.m.ok_or(SynthesisError::AssignmentMissing))?;
+        let q = FpVar::new_witness(cs.clone(), || self.q.ok_or(SynthesisError::AssignmentMissing))?;
+        let r = FpVar::new_input(cs.clone(), || self.r.ok_or(SynthesisError::AssignmentMissing))?;
+
+        // Bounding q, r and m keeps q * m + r below the modulus, so the equation cannot wrap around
+        enforce_bit_decomposition(cs.clone(), &q, self.num_bits)?;
+        enforce_bit_decomposition(cs.clone(), &r, self.num_bits)?;
+        // r < m: m - r - 1 must be a small non-negative integer, which also rules out m = 0
+        let one = FpVar::constant(Fr::from(1u64));
+        enforce_bit_decomposition(cs.clone(), &(&m - &r - &one), self.num_bits)?;
+
+        (&q * &m + &r).enforce_equal(&x)?;
+
+        Ok(())
+    }
+}
+
+
+#[derive(Clone)]
+pub struct PowerCircuit {
+    /// Public
+    pub base: Option<Fr>,
+    pub exponent: Option<Fr>,
+    /// Public: base^exponent
+    pub result: Option<Fr>,
+    /// Bit width of the exponent
+    pub max_bits: usize,
+}
+
+impl PowerCircuit {
+    /// Setup shape for exponents below 2^max_bits (all assignments missing)
+    pub fn setup_shape(max_bits: usize) -> Self {
+        PowerCircuit { base: None, exponent: None, result: None, max_bits }
+    }
+
+    /// Instance for base^exponent
+    pub fn instance(base: u64, exponent: u64, max_bits: usize) -> Self {
+        let base = Fr::from(base);
+        PowerCircuit {
+            base: Some(base),
+            exponent: Some(Fr::from(exponent)),
+            result: Some(base.pow([exponent])),
+            max_bits,
+        }
+    }
+}
+
+impl ConstraintSynthesizer<Fr> for PowerCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        let base = FpVar::new_input(cs.clone(), || self.base.ok_or(SynthesisError::AssignmentMissing))?;
+        let exponent = FpVar::new_witness(cs.clone(), || self.exponent.ok_or(SynthesisError::AssignmentMissing))?;
+        let result = FpVar::new_input(cs.clone(), || self.result.ok_or(SynthesisError::AssignmentMissing))?;
+
+        // Exponents that do not fit in max_bits leave the decomposition unsatisfiable
+        let bits = enforce_bit_decomposition(cs.clone(), &exponent, self.max_bits)?;
+
+        // Square-and-multiply from the most significant bit: acc = acc^2, times base when the bit is set
+        let mut acc = FpVar::constant(Fr::from(1u64));
+        for bit in bits.iter().rev() {
+            acc = acc.square()?;
+            acc = bit.select(&(&acc * &base), &acc)?;
+        }
+        acc.enforce_equal(&result)?;
+
+        Ok(())
+    }
+}
+
+
+/// What `RangeWithBitCircuit` reveals about its hidden value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitOutput {
+    /// The bit at this little-endian index, as 0 or 1
+    Bit(usize),
+    /// Position of the highest set bit plus one (0 for a zero value)
+    BitLength,
+}
+
+impl BitOutput {
+    /// The public input a verifier expects for `value`
+    pub fn of(self, value: u64) -> Fr {
+        match self {
+            // Bits past the top of a u64 are 0, not a shift overflow
+            BitOutput::Bit(i) => Fr::from(u32::try_from(i).ok().and_then(|i| value.checked_shr(i)).unwrap_or(0) & 1),
+            BitOutput::BitLength => Fr::from((u64::BITS - value.leading_zeros()) as u64),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RangeWithBitCircuit {
+    pub value: Option<Fr>,
+    pub num_bits: usize,
+    pub output: BitOutput,
+    /// Public: the revealed bit or bit length
+    pub claimed: Option<Fr>,
+}
+
+impl RangeWithBitCircuit {
+    /// Setup shape for a `num_bits`-bit value revealing `output` (all assignments missing)
+    pub fn setup_shape(num_bits: usize, output: BitOutput) -> Self {
+        RangeWithBitCircuit { value: None, num_bits, output, claimed: None }
+    }
+
+    /// Instance for `value`, claiming what `output` reveals about it
+    pub fn instance(value: u64, num_bits: usize, output: BitOutput) -> Self {
+        RangeWithBitCircuit { value: Some(Fr::from(value)), num_bits, output, claimed: Some(output.of(value)) }
+    }
+}
+
+impl ConstraintSynthesizer<Fr> for RangeWithBitCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        let value = FpVar::new_witness(cs.clone(), || self.value.ok_or(SynthesisError::AssignmentMissing))?;
+        let claimed = FpVar::new_input(cs.clone(), || self.claimed.ok_or(SynthesisError::AssignmentMissing))?;
+
+        let bits = enforce_bit_decomposition(cs.clone(), &value, self.num_bits)?;
+
+        let revealed = match self.output {
+            // A bit outside the range has no decomposition variable to reveal
+            BitOutput::Bit(i) => FpVar::from(bits.get(i).ok_or(SynthesisError::Unsatisfiable)?.clone()),
+            BitOutput::BitLength => {
+                // seen_i = bit_i OR seen_{i+1}; the bit length counts the positions at or below the top set bit
+                let mut seen = Boolean::FALSE;
+                let mut length = FpVar::<Fr>::zero();
+                for bit in bits.iter().rev() {
+                    seen = seen.or(bit)?;
+                    length += FpVar::from(seen.clone());
+                }
+                length
+            }
+        };
+        revealed.enforce_equal(&claimed)?;
+
+        Ok(())
+    }
+}
+
+
+#[derive(Clone)]
+pub struct ParityCircuit {
+    pub value: Option<Fr>,
+    pub num_bits: usize,
+}
+
+impl ParityCircuit {
+    /// Setup shape for a `num_bits`-bit value (assignment missing)
+    pub fn setup_shape(num_bits: usize) -> Self {
+        ParityCircuit { value: None, num_bits }
+    }
+
+    /// Instance for `value`; the public parity is derived during synthesis
+    pub fn instance(value: u64, num_bits: usize) -> Self {
+        ParityCircuit { value: Some(Fr::from(value)), num_bits }
+    }
+}
+
+impl ConstraintSynthesizer<Fr> for ParityCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        // Parity needs at least one bit
+        if self.num_bits == 0 {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let value = FpVar::new_witness(cs.clone(), || self.value.ok_or(SynthesisError::AssignmentMissing))?;
+
+        // Values that do not fit in num_bits leave the decomposition unsatisfiable
+        let bits = enforce_bit_decomposition(cs.clone(), &value, self.num_bits)?;
+        let parity = Boolean::new_input(cs.clone(), || bits[0].value())?;
+        parity.enforce_equal(&bits[0])?;
+
+        Ok(())
+    }
+}
+
+
+#[derive(Clone)]
+pub struct PoseidonPreimageCircuit {
+    /// Cleared on drop
+    pub secret: Option<Zeroizing<Fr>>,
+    pub hash: Option<Fr>,
+}
+
+impl PoseidonPreimageCircuit {
+    /// Setup shape (all assignments missing)
+    pub fn setup_shape() -> Self {
+        PoseidonPreimageCircuit { secret: None, hash: None }
+    }
+
+    /// Instance for `secret`, with its public hash computed natively
+    pub fn instance(secret: Fr) -> Self {
+        PoseidonPreimageCircuit { secret: Some(Zeroizing::new(secret)), hash: Some(poseidon_sponge_hash(&[secret])) }
+    }
+}
+
+impl ConstraintSynthesizer<Fr> for PoseidonPreimageCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        let secret = FpVar::new_witness(cs.clone(), || self.secret.as_deref().copied().ok_or(SynthesisError::AssignmentMissing))?;
+        let hash = FpVar::new_input(cs.clone(), || self.hash.ok_or(SynthesisError::AssignmentMissing))?;
+
+        poseidon_sponge_hash_gadget(cs.clone(), &[secret])?.enforce_equal(&hash)?;
+
+        Ok(())
+    }
+}
+
+
+/// Like `PoseidonPreimageCircuit`, but the hash is derived in-circuit instead of being
+/// supplied: the public input is assigned from the gadget's output, so the caller learns
+/// it from the synthesized instance rather than having to know it up front.
+#[derive(Clone)]
+pub struct PoseidonHashOutputCircuit {
+    /// Cleared on drop
+    pub secret: Option<Zeroizing<Fr>>,
+}
+
+impl PoseidonHashOutputCircuit {
+    /// Setup shape (all assignments missing)
+    pub fn setup_shape() -> Self {
+        PoseidonHashOutputCircuit { secret: None }
+    }
+
+    pub fn instance(secret: Fr) -> Self {
+        PoseidonHashOutputCircuit { secret: Some(Zeroizing::new(secret)) }
+    }
+}
+
+impl ConstraintSynthesizer<Fr> for PoseidonHashOutputCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        let secret = FpVar::new_witness(cs.clone(), || self.secret.as_deref().copied().ok_or(SynthesisError::AssignmentMissing))?;
+        let digest = poseidon_sponge_hash_gadget(cs.clone(), &[secret])?;
+
+        let hash = FpVar::new_input(cs.clone(), || digest.value())?;
+        hash.enforce_equal(&digest)?;
+
+        Ok(())
+    }
+}
+
+
+/// Proves that (r, s) is a signature on `message_hash` under `public_key`, by knowledge
+/// of the secret key and nonce: public_key = H(sk), r = H(k), s = k + H(r, public_key, message_hash) * sk
+#[derive(Clone)]
+pub struct PoseidonSignatureCircuit {
+    /// Public inputs, in this order
+    pub message_hash: Option<Fr>,
+    pub public_key: Option<Fr>,
+    pub r: Option<Fr>,
+    pub s: Option<Fr>,
+    /// Cleared on drop
+    pub secret_key: Option<Zeroizing<Fr>>,
+    /// Cleared on drop
+    pub nonce: Option<Zeroizing<Fr>>,
+}
+
+impl PoseidonSignatureCircuit {
+    /// Setup shape (all assignments missing)
+    pub fn setup_shape() -> Self {
+        PoseidonSignatureCircuit { message_hash: None, public_key: None, r: None, s: None, secret_key: None, nonce: None }
+    }
+
+    /// Instance signing `message_hash` with `secret_key` and `nonce`, computing the public values natively
+    pub fn instance(secret_key: Fr, nonce: Fr, message_hash: Fr) -> Self {
+        let signature = sign_with_nonce(secret_key, nonce, message_hash);
+        PoseidonSignatureCircuit {
+            message_hash: Some(message_hash),
+            public_key: Some(public_key(secret_key)),
+            r: Some(signature.r),
+            s: Some(signature.s),
+            secret_key: Some(Zeroizing::new(secret_key)),
+            nonce: Some(Zeroizing::new(nonce)),
+        }
+    }
+}
+
+impl ConstraintSynthesizer<Fr> for PoseidonSignatureCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        let message_hash = FpVar::new_input(cs.clone(), || self.message_hash.ok_or(SynthesisError::AssignmentMissing))?;
+        let public_key = FpVar::new_input(cs.clone(), || self.public_key.ok_or(SynthesisError::AssignmentMissing))?;
+        let r = FpVar::new_input(cs.clone(), || self.r.ok_or(SynthesisError::AssignmentMissing))?;
+        let s = FpVar::new_input(cs.clone(), || self.s.ok_or(SynthesisError::AssignmentMissing))?;
+        let secret_key =
+            FpVar::new_witness(cs.clone(), || self.secret_key.as_deref().copied().ok_or(SynthesisError::AssignmentMissing))?;
+        let nonce = FpVar::new_witness(cs.clone(), || self.nonce.as_deref().copied().ok_or(SynthesisError::AssignmentMissing))?;
+
+        poseidon_sponge_hash_gadget(cs.clone(), std::slice::from_ref(&secret_key))?.enforce_equal(&public_key)?;
+        poseidon_sponge_hash_gadget(cs.clone(), std::slice::from_ref(&nonce))?.enforce_equal(&r)?;
+        let e = poseidon_sponge_hash_gadget(cs.clone(), &[r, public_key, message_hash])?;
+        (nonce + e * secret_key).enforce_equal(&s)?;
+
+        Ok(())
+    }
+}
+
+
+/// A statement about a list of field-element inputs, independent of whether they are public
+pub trait InputRelation {
+    fn enforce(&self, cs: ConstraintSystemRef<Fr>, inputs: &[FpVar<Fr>]) -> Result<(), SynthesisError>;
+}
+
+/// Knowledge of a private root x of the polynomial whose coefficients (lowest degree first) are the inputs
+#[derive(Clone)]
+pub struct RootRelation {
+    pub x: Option<Fr>,
+}
+
+impl InputRelation for RootRelation {
+    fn enforce(&self, cs: ConstraintSystemRef<Fr>, coeffs: &[FpVar<Fr>]) -> Result<(), SynthesisError> {
+        let x = FpVar::new_witness(cs, || self.x.ok_or(SynthesisError::AssignmentMissing))?;
+
+        // Horner: ((c_n * x + c_{n-1}) * x + ...) + c_0
+        let mut acc = FpVar::<Fr>::zero();
+        for c in coeffs.iter().rev() {
+            acc = acc * &x + c;
+        }
+        acc.enforce_equal(&FpVar::zero())
+    }
+}
+
+/// `relation` with every input allocated as a public input
+#[derive(Clone)]
+pub struct PublicInputsCircuit<R> {
+    pub inputs: Vec<Option<Fr>>,
+    pub relation: R,
+}
+
+impl<R> PublicInputsCircuit<R> {
+    /// Setup shape for `n` inputs; `relation` should carry no assignments either
+    pub fn setup_shape(n: usize, relation: R) -> Self {
+        PublicInputsCircuit { inputs: vec![None; n], relation }
+    }
+
+    pub fn instance(inputs: &[Fr], relation: R) -> Self {
+        PublicInputsCircuit { inputs: inputs.iter().copied().map(Some).collect(), relation }
+    }
+}
+
+impl<R: InputRelation> ConstraintSynthesizer<Fr> for PublicInputsCircuit<R> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        let inputs = self
+            .inputs
+            .iter()
+            .map(|x| FpVar::new_input(cs.clone(), || x.ok_or(SynthesisError::AssignmentMissing)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.relation.enforce(cs, &inputs)
+    }
+}
+
+/// `relation` over private inputs, with poseidon_sponge_hash(inputs) = inputs_hash as the only public input
+#[derive(Clone)]
+pub struct HashedInputsCircuit<R> {
+    /// Private, cleared on drop
+    pub inputs: Zeroizing<Vec<Option<Fr>>>,
+    pub inputs_hash: Option<Fr>,
+    pub relation: R,
+}
+
+impl<R> HashedInputsCircuit<R> {
+    /// Setup shape for `n` inputs; `relation` should carry no assignments either
+    pub fn setup_shape(n: usize, relation: R) -> Self {
+        HashedInputsCircuit { inputs: Zeroizing::new(vec![None; n]), inputs_hash: None, relation }
+    }
+
+    /// Instance for `inputs`, with their public commitment computed natively
+    pub fn instance(inputs: &[Fr], relation: R) -> Self {
+        HashedInputsCircuit {
+            inputs: Zeroizing::new(inputs.iter().copied().map(Some).collect()),
+            inputs_hash: Some(poseidon_sponge_hash(inputs)),
+            relation,
+        }
+    }
+}
+
+impl<R: InputRelation> ConstraintSynthesizer<Fr> for HashedInputsCircuit<R> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        let inputs = self
+            .inputs
+            .iter()
+            .map(|x| FpVar::new_witness(cs.clone(), || x.ok_or(SynthesisError::AssignmentMissing)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let inputs_hash = FpVar::new_input(cs.clone(), || self.inputs_hash.ok_or(SynthesisError::AssignmentMissing))?;
+
+        poseidon_sponge_hash_gadget(cs.clone(), &inputs)?.enforce_equal(&inputs_hash)?;
+        self.relation.enforce(cs, &inputs)
+    }
+}
+
+
+/// `inner` with a `context_nonce` appended to its public inputs.
+/// A Groth16 proof only verifies against the exact public input vector it was made for, so a
+/// proof produced for one context (a session, a chain id, a request id) cannot be replayed in
+/// another: the verifier supplies its own nonce, and a proof for any other nonce fails.
+/// The nonce follows the inner circuit's public inputs in the vector the verifier passes.
+#[derive(Clone)]
+pub struct NonceBound<C> {
+    pub inner: C,
+    pub context_nonce: Option<Fr>,
+}
+
+impl<C> NonceBound<C> {
+    /// Setup shape around the inner circuit's own setup shape
+    pub fn setup_shape(inner: C) -> Self {
+        NonceBound { inner, context_nonce: None }
+    }
+
+    pub fn instance(inner: C, context_nonce: Fr) -> Self {
+        NonceBound { inner, context_nonce: Some(context_nonce) }
+    }
+}
+
+impl<C: ConstraintSynthesizer<Fr>> ConstraintSynthesizer<Fr> for NonceBound<C> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        self.inner.generate_constraints(cs.clone())?;
+
+        // nonce == nonce: constrains nothing, but pins the nonce into the instance vector
+        let nonce = FpVar::new_input(cs, || self.context_nonce.ok_or(SynthesisError::AssignmentMissing))?;
+        nonce.enforce_equal(&nonce)?;
+
+        Ok(())
+    }
+}
+
+
+/// Whether a circuit carries the assignments proving needs.
+/// A setup shape passed to the prover would otherwise fail deep inside arkworks with a
+/// bare `AssignmentMissing`; `prove_circuit` checks this first and names the field.
+pub trait Assignment {
+    /// The first field without an assignment, or `None` for a fully assigned instance
+    fn missing_assignment(&self) -> Option<&'static str>;
+
+    fn is_instance(&self) -> bool {
+        self.missing_assignment().is_none()
+    }
+}
+
+fn first_missing(fields: &[(&'static str, bool)]) -> Option<&'static str> {
+    fields.iter().find(|(_, assigned)| !assigned).map(|(name, _)| *name)
+}
+
+fn all_assigned(values: &[Option<Fr>]) -> bool {
+    values.iter().all(Option::is_some)
+}
+
+impl Assignment for MulCircuit {
+    fn missing_assignment(&self) -> Option<&'static str> {
+        first_missing(&[("a", self.a.is_some()), ("b", self.b.is_some()), ("c", self.c.is_some())])
+    }
+}
+
+impl Assignment for SumCircuit {
+    fn missing_assignment(&self) -> Option<&'static str> {
+        first_missing(&[("xs", all_assigned(&self.xs)), ("total", self.total.is_some())])
+    }
+}
+
+impl Assignment for WeightedSumCircuit {
+    fn missing_assignment(&self) -> Option<&'static str> {
+        first_missing(&[
+            ("weights", all_assigned(&self.weights)),
+            ("xs", all_assigned(&self.xs)),
+            ("total", self.total.is_some()),
+        ])
+    }
+}
+
+impl Assignment for FactorCircuit {
+    fn missing_assignment(&self) -> Option<&'static str> {
+        first_missing(&[("p", self.p.is_some()), ("q", self.q.is_some()), ("n", self.n.is_some())])
+    }
+}
+
+impl Assignment for ModCircuit {
+    fn missing_assignment(&self) -> Option<&'static str> {
+        first_missing(&[
+            ("x", self.x.is_some()),
+            ("m", self.m.is_some()),
+            ("q", self.q.is_some()),
+            ("r", self.r.is_some()),
+        ])
+    }
+}
+
+impl Assignment for PowerCircuit {
+    fn missing_assignment(&self) -> Option<&'static str> {
+        first_missing(&[
+            ("base", self.base.is_some()),
+            ("exponent", self.exponent.is_some()),
+            ("result", self.result.is_some()),
+        ])
+    }
+}
+
+impl Assignment for RangeWithBitCircuit {
+    fn missing_assignment(&self) -> Option<&'static str> {
+        first_missing(&[("value", self.value.is_some()), ("claimed", self.claimed.is_some())])
+    }
+}
+
+impl Assignment for ParityCircuit {
+    fn missing_assignment(&self) -> Option<&'static str> {
+        first_missing(&[("value", self.value.is_some())])
+    }
+}
+
+impl Assignment for PoseidonPreimageCircuit {
+    fn missing_assignment(&self) -> Option<&'static str> {
+        first_missing(&[("secret", self.secret.is_some()), ("hash", self.hash.is_some())])
+    }
+}
+
+impl Assignment for PoseidonHashOutputCircuit {
+    fn missing_assignment(&self) -> Option<&'static str> {
+        first_missing(&[("secret", self.secret.is_some())])
+    }
+}
+
+impl Assignment for PoseidonSignatureCircuit {
+    fn missing_assignment(&self) -> Option<&'static str> {
+        first_missing(&[
+            ("message_hash", self.message_hash.is_some()),
+            ("public_key", self.public_key.is_some()),
+            ("r", self.r.is_some()),
+            ("s", self.s.is_some()),
+            ("secret_key", self.secret_key.is_some()),
+            ("nonce", self.nonce.is_some()),
+        ])
+    }
+}
+
+impl<C: Assignment> Assignment for NonceBound<C> {
+    fn missing_assignment(&self) -> Option<&'static str> {
+        self.inner.missing_assignment().or(first_missing(&[("context_nonce", self.context_nonce.is_some())]))
+    }
+}
+
+// The relation's own witnesses are opaque here, so only the inputs are checked
+impl<R> Assignment for PublicInputsCircuit<R> {
+    fn missing_assignment(&self) -> Option<&'static str> {
+        first_missing(&[("inputs", all_assigned(&self.inputs))])
+    }
+}
+
+impl<R> Assignment for HashedInputsCircuit<R> {
+    fn missing_assignment(&self) -> Option<&'static str> {
+        first_missing(&[("inputs", all_assigned(&self.inputs)), ("inputs_hash", self.inputs_hash.is_some())])
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{assert_proves, assert_rejects};
+    use ark_relations::r1cs::ConstraintSystem;
+
+    fn sum_is_satisfied(xs: &[u64], total: u64) -> bool {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let circuit = SumCircuit {
+            xs: xs.iter().map(|x| Some(Fr::from(*x))).collect(),
+            total: Some(Fr::from(total)),
+        };
+        circuit.generate_constraints(cs.clone()).expect("synthesis failed");
+        cs.is_satisfied().expect("satisfiability check failed")
+    }
+
+    fn nonzero_mul(a: u64, b: u64) -> MulCircuit {
+        MulCircuit { nonzero: true, ..MulCircuit::instance(a, b) }
+    }
+
+    #[test]
+    fn test_zero_factor_allowed_in_default_mode() {
+        assert_proves(MulCircuit::instance(0, 4), &[Fr::from(0u64)]);
+        assert_proves(MulCircuit::instance(3, 4), &[Fr::from(12u64)]);
+        assert_rejects(MulCircuit::instance(3, 4), &[Fr::from(13u64)]);
+    }
+
+    #[test]
+    fn test_zero_factor_rejected_in_nonzero_mode() {
+        let unsatisfied = |circuit| matches!(crate::check_satisfied(circuit), Err(crate::ProverError::Unsatisfied { .. }));
+        assert!(unsatisfied(nonzero_mul(0, 4)));
+        assert!(unsatisfied(nonzero_mul(3, 0)));
+        assert_proves(nonzero_mul(3, 4), &[Fr::from(12u64)]);
+        assert_rejects(nonzero_mul(3, 4), &[Fr::from(0u64)]);
+    }
+
+    #[test]
+    fn test_generate_nonzero_proof_verifies() {
+        let (proof, c, pk) = crate::generate_nonzero_proof(3, 4).expect("proof generation failed");
+        assert!(crate::verify_proof(&proof, c, &pk.vk).expect("verification failed"));
+
+        for (a, b) in [(0, 4), (3, 0)] {
+            let err = crate::generate_nonzero_proof(a, b).expect_err("a zero factor must be refused");
+            assert!(matches!(err, crate::ProverError::Unsatisfied { .. }), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_mul_inputs_from_json_prove() {
+        let inputs: MulInputs = serde_json::from_str(r#"{"a":3,"b":4}"#).expect("parsing inputs failed");
+        assert_eq!(inputs, MulInputs { a: 3, b: 4 });
+
+        let (pk, _) = crate::setup_circuit_specific(MulCircuit::setup_shape()).expect("setup failed");
+        let proof = crate::prove_circuit(&pk, MulCircuit::from_inputs(inputs)).expect("proving failed");
+        assert!(crate::verify_proof(&proof, Fr::from(12u64), &pk.vk).expect("verification failed"));
+
+        assert!(serde_json::from_str::<MulInputs>(r#"{"a":3}"#).is_err());
+    }
+
+    #[test]
+    fn test_sum_circuit_correct_total() {
+        assert!(sum_is_satisfied(&[1, 2, 3, 4], 10));
+    }
+
+    #[test]
+    fn test_sum_circuit_off_by_one_total_fails() {
+        assert!(!sum_is_satisfied(&[1, 2, 3, 4], 11));
+    }
+
+    #[test]
+    fn test_weighted_sum_circuit_correct_and_wrong_total() {
+        // 2*5 + 3*7 + 0*9 = 31
+        let circuit = WeightedSumCircuit::instance(&[2, 3, 0], &[5, 7, 9]);
+        assert_eq!(circuit.total, Some(Fr::from(31u64)));
+        let public = |total: u64| [2u64, 3, 0, total].map(Fr::from);
+        assert_proves(circuit.clone(), &public(31));
+        assert_rejects(circuit.clone(), &public(30));
+
+        let wrong_total = WeightedSumCircuit { total: Some(Fr::from(30u64)), ..circuit };
+        let err = crate::check_satisfied(wrong_total).expect_err("wrong total must not be satisfiable");
+        assert!(matches!(err, crate::ProverError::Unsatisfied { .. }));
+    }
+
+    #[test]
+    fn test_weighted_sum_circuit_with_mismatched_lengths_fails_synthesis() {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let circuit = WeightedSumCircuit::instance(&[1, 2], &[3]);
+        assert!(matches!(circuit.generate_constraints(cs), Err(SynthesisError::Unsatisfiable)));
+    }
+
+    fn factor_is_satisfied(p: u64, q: u64) -> bool {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let circuit = FactorCircuit::instance(p, q);
+        circuit.generate_constraints(cs.clone()).expect("synthesis failed");
+        cs.is_satisfied().expect("satisfiability check failed")
+    }
+
+    #[test]
+    fn test_factor_circuit_accepts_nontrivial_factors() {
+        assert!(factor_is_satisfied(5, 7));
+    }
+
+    #[test]
+    fn test_factor_circuit_rejects_trivial_factorization() {
+        assert!(!factor_is_satisfied(1, 35));
+        assert!(!factor_is_satisfied(35, 1));
+        assert!(!factor_is_satisfied(0, 35));
+    }
+
+    #[test]
+    fn test_nonce_bound_proof_does_not_replay_under_another_nonce() {
+        let (nonce_a, nonce_b) = (Fr::from(1001u64), Fr::from(1002u64));
+        let c = Fr::from(12u64);
+
+        let bound = NonceBound::instance(MulCircuit::instance(3, 4), nonce_a);
+        assert_proves(bound.clone(), &[c, nonce_a]);
+        assert_rejects(bound.clone(), &[c, nonce_b]);
+        // The nonce is appended after the inner public inputs, not before
+        assert_rejects(bound, &[nonce_a, c]);
+
+        let shape = NonceBound::setup_shape(MulCircuit::setup_shape());
+        assert_eq!(shape.missing_assignment(), Some("a"));
+        let unbound = NonceBound { inner: MulCircuit::instance(3, 4), context_nonce: None };
+        assert_eq!(unbound.missing_assignment(), Some("context_nonce"));
+    }
+
+    #[test]
+    fn test_secret_witness_is_zeroized_on_drop() {
+        use std::mem::ManuallyDrop;
+
+        let mut circuit = ManuallyDrop::new(PoseidonPreimageCircuit::instance(Fr::from(42u64)));
+        let secret: *const Fr = &**circuit.secret.as_ref().expect("instance assigns secret");
+        // `ManuallyDrop` keeps the storage in place after `drop`, so the raw pointer can inspect it
+        let bytes = unsafe {
+            ManuallyDrop::drop(&mut circuit);
+            std::slice::from_raw_parts(secret.cast::<u8>(), std::mem::size_of::<Fr>())
+        };
+        assert!(bytes.iter().all(|&b| b == 0), "secret survived the drop");
+    }
+
+    #[test]
+    fn test_unsatisfiable_instances_error_instead_of_proving() {
+        let secret = Fr::from(42u64);
+        let wrong_hash = crate::poseidon::poseidon_sponge_hash(&[secret]) + Fr::from(1u64);
+        let wrong = PoseidonPreimageCircuit { secret: Some(Zeroizing::new(secret)), hash: Some(wrong_hash) };
+
+        // A correct preimage does not prove a different hash either
+        assert_rejects(PoseidonPreimageCircuit::instance(secret), &[wrong_hash]);
+
+        let err = crate::check_satisfied(wrong).expect_err("wrong hash must not be satisfiable");
+        assert!(matches!(err, crate::ProverError::Unsatisfied { .. }));
+        assert!(crate::check_satisfied(PoseidonPreimageCircuit::instance(secret)).is_ok());
+
+        let err = crate::prove_factorization(1, 35).expect_err("trivial factorization must be refused");
+        assert!(matches!(err, crate::ProverError::Unsatisfied { .. }));
+    }
+
+    #[test]
+    fn test_prove_factorization_verifies() {
+        let (proof, n, pk) = crate::prove_factorization(5, 7).expect("proof generation failed");
+        assert_eq!(n, Fr::from(35u64));
+        assert!(crate::verify_proof(&proof, n, &pk.vk).expect("verification failed"));
+    }
+
+    #[test]
+    fn test_prove_mod_reveals_the_remainder() {
+        let (proof, inputs, pk) = crate::prove_mod(17, 5).expect("proof generation failed");
+        let public = |x: u64, m: u64, r: u64| [x, m, r].map(Fr::from);
+        assert_eq!(inputs, public(17, 5, 2));
+        let verifies = |inputs: &[Fr]| crate::verify_proof_with_inputs(&proof, inputs, &pk.vk).unwrap();
+        assert!(verifies(&inputs));
+        assert!(!verifies(&public(17, 5, 3)));
+        // Same remainder, another x or m
+        assert!(!verifies(&public(22, 5, 2)));
+        assert!(!verifies(&public(17, 15, 2)));
+
+        let err = crate::prove_mod(17, 0).expect_err("zero modulus must be refused");
+        assert!(matches!(err, crate::ProverError::InvalidConfig(_)));
+    }
+
+    #[test]
+    fn test_mod_circuit_refuses_operands_wide_enough_to_wrap() {
+        let synthesize = |num_bits: usize| {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            cs.set_mode(ark_relations::r1cs::SynthesisMode::Setup);
+            ModCircuit::setup_shape(num_bits).generate_constraints(cs)
+        };
+        assert!(synthesize(MAX_MOD_BITS).is_ok());
+        assert!(matches!(synthesize(MAX_MOD_BITS + 1), Err(SynthesisError::Unsatisfiable)));
+    }
+
+    #[test]
+    fn test_mod_circuit_rejects_wrong_remainder() {
+        // 17 = 3 * 5 + 2, but also 17 = 2 * 5 + 7 with a remainder that is not below m
+        let wrong = |q: u64, r: u64| ModCircuit {
+            x: Some(Fr::from(17u64)),
+            m: Some(Fr::from(5u64)),
+            q: Some(Fr::from(q)),
+            r: Some(Fr::from(r)),
+            num_bits: 64,
+        };
+        for circuit in [wrong(3, 3), wrong(2, 7)] {
+            let err = crate::check_satisfied(circuit).expect_err("wrong remainder must not be satisfiable");
+            assert!(matches!(err, crate::ProverError::Unsatisfied { .. }));
+        }
+    }
+
+    #[test]
+    fn test_power_circuit_proves_base_to_the_exponent() {
+        // 3^13 = 1594323
+        let circuit = PowerCircuit::instance(3, 13, 8);
+        assert_eq!(circuit.result, Some(Fr::from(1_594_323u64)));
+        assert_proves(circuit.clone(), &[Fr::from(3u64), Fr::from(1_594_323u64)]);
+        assert_rejects(circuit, &[Fr::from(3u64), Fr::from(1_594_324u64)]);
+    }
+
+    #[test]
+    fn test_power_circuit_rejects_wrong_result_or_oversized_exponent() {
+        let wrong_result = PowerCircuit { result: Some(Fr::from(81u64)), ..PowerCircuit::instance(3, 5, 8) };
+        let oversized = PowerCircuit::instance(2, 256, 8);
+        for circuit in [wrong_result, oversized] {
+            let err = crate::check_satisfied(circuit).expect_err("circuit must not be satisfiable");
+            assert!(matches!(err, crate::ProverError::Unsatisfied { .. }));
+        }
+    }
+
+    fn range_with_bit_is_satisfied(value: u64, output: BitOutput, claimed: u64) -> bool {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let circuit = RangeWithBitCircuit {
+            value: Some(Fr::from(value)),
+            num_bits: 16,
+            output,
+            claimed: Some(Fr::from(claimed)),
+        };
+        circuit.generate_constraints(cs.clone()).expect("synthesis failed");
+        cs.is_satisfied().expect("satisfiability check failed")
+    }
+
+    #[test]
+    fn test_range_with_bit_exposes_witness_bit() {
+        // 0b10_0110: bit 5 set, bit 0 clear, bit length 6
+        let value = 38;
+        assert_eq!(BitOutput::Bit(5).of(value), Fr::from(1u64));
+        assert!(range_with_bit_is_satisfied(value, BitOutput::Bit(5), 1));
+        assert!(range_with_bit_is_satisfied(value, BitOutput::Bit(0), 0));
+        assert!(range_with_bit_is_satisfied(value, BitOutput::BitLength, 6));
+        assert!(range_with_bit_is_satisfied(0, BitOutput::BitLength, 0));
+    }
+
+    #[test]
+    fn test_range_with_bit_rejects_wrong_claim() {
+        assert!(!range_with_bit_is_satisfied(38, BitOutput::Bit(5), 0));
+        assert!(!range_with_bit_is_satisfied(38, BitOutput::Bit(0), 1));
+        assert!(!range_with_bit_is_satisfied(38, BitOutput::BitLength, 5));
+        // Out of range: 2^16 does not fit in 16 bits
+        assert!(!range_with_bit_is_satisfied(1 << 16, BitOutput::Bit(0), 0));
+    }
+
+    #[test]
+    fn test_bit_decomposition_as_wide_as_the_field_is_an_error() {
+        fn synthesize_shape(circuit: impl ConstraintSynthesizer<Fr>) -> Result<(), SynthesisError> {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            cs.set_mode(ark_relations::r1cs::SynthesisMode::Setup);
+            circuit.generate_constraints(cs)
+        }
+        assert!(matches!(
+            synthesize_shape(RangeWithBitCircuit::setup_shape(254, BitOutput::BitLength)),
+            Err(SynthesisError::Unsatisfiable)
+        ));
+        assert!(matches!(synthesize_shape(ModCircuit::setup_shape(300)), Err(SynthesisError::Unsatisfiable)));
+        assert!(matches!(synthesize_shape(PowerCircuit::setup_shape(254)), Err(SynthesisError::Unsatisfiable)));
+        assert!(synthesize_shape(PowerCircuit::setup_shape(253)).is_ok());
+    }
+
+    #[test]
+    fn test_range_with_bit_beyond_the_range_errors_instead_of_panicking() {
+        assert_eq!(BitOutput::Bit(63).of(u64::MAX), Fr::from(1u64));
+        for i in [64, 200, usize::MAX] {
+            assert_eq!(BitOutput::Bit(i).of(u64::MAX), Fr::from(0u64));
+        }
+
+        let circuit = RangeWithBitCircuit::instance(38, 16, BitOutput::Bit(16));
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        assert!(matches!(circuit.generate_constraints(cs), Err(SynthesisError::Unsatisfiable)));
+    }
+
+    #[test]
+    fn test_hashed_inputs_shrink_calldata_and_both_modes_verify() {
+        use crate::poseidon::poseidon_sponge_hash;
+        use ark_bn254::Bn254;
+        use ark_serialize::CanonicalSerialize;
+
+        // (x - 2)(x - 3)(x - 5)(x - 7) = x^4 - 17x^3 + 101x^2 - 247x + 210, with root x = 5
+        let coeffs: Vec<Fr> = [210i64, -247, 101, -17, 1].iter().map(|&c| Fr::from(c)).collect();
+        let root = RootRelation { x: Some(Fr::from(5u64)) };
+        let calldata_len =
+            |proof: &ark_groth16::Proof<Bn254>, public: &[Fr]| proof.compressed_size() + 32 * public.len();
+
+        let proof = assert_proves(PublicInputsCircuit::instance(&coeffs, root.clone()), &coeffs);
+        let plain_len = calldata_len(&proof, &coeffs);
+
+        let hash = poseidon_sponge_hash(&coeffs);
+        let hashed = HashedInputsCircuit::instance(&coeffs, root);
+        let proof = assert_proves(hashed.clone(), &[hash]);
+        assert_rejects(hashed, &[hash + Fr::from(1u64)]);
+        let hashed_len = calldata_len(&proof, &[hash]);
+
+        assert_eq!(plain_len, 128 + 5 * 32);
+        assert_eq!(hashed_len, 128 + 32);
+    }
+
+    #[test]
+    fn test_hashed_inputs_reject_mismatched_hash_or_wrong_root() {
+        use crate::poseidon::poseidon_sponge_hash;
+
+        let coeffs = [Fr::from(6u64), -Fr::from(5u64), Fr::from(1u64)]; // (x - 2)(x - 3)
+        let is_satisfied = |x: u64, hash: Fr| {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            HashedInputsCircuit {
+                inputs: Zeroizing::new(coeffs.iter().map(|c| Some(*c)).collect()),
+                inputs_hash: Some(hash),
+                relation: RootRelation { x: Some(Fr::from(x)) },
+            }
+            .generate_constraints(cs.clone())
+            .expect("synthesis failed");
+            cs.is_satisfied().expect("satisfiability check failed")
+        };
+
+        let hash = poseidon_sponge_hash(&coeffs);
+        assert!(is_satisfied(2, hash));
+        assert!(is_satisfied(3, hash));
+        assert!(!is_satisfied(4, hash));
+        assert!(!is_satisfied(2, hash + Fr::from(1u64)));
+    }
+
+    fn parity_is_satisfied(value: u64, num_bits: usize) -> bool {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let circuit = ParityCircuit::instance(value, num_bits);
+        circuit.generate_constraints(cs.clone()).expect("synthesis failed");
+        cs.is_satisfied().expect("satisfiability check failed")
+    }
+
+    #[test]
+    fn test_parity_circuit_rejects_values_outside_num_bits() {
+        assert!(parity_is_satisfied(255, 8));
+        assert!(!parity_is_satisfied(256, 8));
+    }
+
+    #[test]
+    fn test_parity_circuit_without_bits_is_an_error() {
+        let err = crate::check_satisfied(ParityCircuit::instance(3, 0)).expect_err("zero bits must be refused");
+        assert!(matches!(err, crate::ProverError::Synthesis(SynthesisError::Unsatisfiable)));
+    }
+
+    #[test]
+    fn test_prove_parity_of_even_and_odd_values() {
+        let (zero, one) = (Fr::from(0u64), Fr::from(1u64));
+        for (value, parity) in [(10u64, zero), (7u64, one)] {
+            let (proof, claimed, pk) = crate::prove_parity(value).expect("proof generation failed");
+            assert_eq!(claimed, parity);
+            assert!(crate::verify_proof(&proof, parity, &pk.vk).expect("verification failed"));
+            assert!(!crate::verify_proof(&proof, one - parity, &pk.vk).expect("verification failed"));
+        }
+    }
+
+    #[test]
+    fn test_prove_sum_verifies() {
+        let (proof, total, pk) = crate::prove_sum(&[5, 6, 7]).expect("proof generation failed");
+        assert_eq!(total, Fr::from(18u64));
+        assert!(crate::verify_proof(&proof, total, &pk.vk).expect("verification failed"));
+        assert!(!crate::verify_proof(&proof, total + Fr::from(1u64), &pk.vk).expect("verification failed"));
+    }
+
+    #[test]
+    fn test_prove_weighted_sum_verifies() {
+        let (proof, total, pk) = crate::prove_weighted_sum(&[4, 1], &[10, 3]).expect("proof generation failed");
+        assert_eq!(total, Fr::from(43u64));
+        let public = |total: Fr| [Fr::from(4u64), Fr::from(1u64), total];
+        let verify = |total: Fr| crate::verify_proof_with_inputs(&proof, &public(total), &pk.vk).expect("verification failed");
+        assert!(verify(total));
+        assert!(!verify(total + Fr::from(1u64)));
+
+        let err = crate::prove_weighted_sum(&[1, 2], &[3]).expect_err("mismatched lengths must be refused");
+        assert!(matches!(err, crate::ProverError::InvalidConfig(_)));
+    }
+
+    #[test]
+    fn test_poseidon_signature_verifies_and_forgeries_fail() {
+        use crate::signature::{message_hash, public_key, sign};
+
+        let secret_key = Fr::from(0xC0FFEEu64);
+        let m = message_hash(b"transfer 10 to alice");
+        let (signature, nonce) = sign(secret_key, m);
+        let circuit = PoseidonSignatureCircuit::instance(secret_key, *nonce, m);
+        assert_eq!((circuit.r, circuit.s), (Some(signature.r), Some(signature.s)));
+
+        let pk = public_key(secret_key);
+        let public = |m: Fr, s: Fr| [m, pk, signature.r, s];
+        assert_proves(circuit.clone(), &public(m, signature.s));
+        // The proof of this signature does not vouch for another message or a tampered s
+        assert_rejects(circuit.clone(), &public(message_hash(b"transfer 10 to mallory"), signature.s));
+        assert_rejects(circuit.clone(), &public(m, signature.s + Fr::from(1u64)));
+
+        // Neither a tampered s nor a key other than the one behind the public key satisfies the circuit
+        let forged_s = PoseidonSignatureCircuit { s: Some(signature.s + Fr::from(1u64)), ..circuit.clone() };
+        let wrong_key = PoseidonSignatureCircuit { secret_key: Some(Zeroizing::new(Fr::from(7u64))), ..circuit };
+        for forged in [forged_s, wrong_key] {
+            let err = crate::check_satisfied(forged).expect_err("forged signature must not be satisfiable");
+            assert!(matches!(err, crate::ProverError::Unsatisfied { .. }));
+        }
+    }
+
+    fn proves_and_verifies<C: ConstraintSynthesizer<Fr>>(setup: C, instance: C, public: &[Fr]) -> bool {
+        use ark_bn254::Bn254;
+        use ark_groth16::Groth16;
+        use ark_snark::SNARK;
+
+        let mut rng = rand::thread_rng();
+        let (pk, vk) = Groth16::<Bn254>::circuit_specific_setup(setup, &mut rng).unwrap();
+        let proof = Groth16::<Bn254>::prove(&pk, instance, &mut rng).unwrap();
+        Groth16::<Bn254>::verify(&vk, public, &proof).unwrap()
+    }
+
+    #[test]
+    fn test_setup_shape_and_instance_constructors_prove() {
+        let mul = MulCircuit::instance(3, 4);
+        assert_eq!(mul.c, Some(Fr::from(12u64)));
+        assert!(proves_and_verifies(MulCircuit::setup_shape(), mul, &[Fr::from(12u64)]));
+
+        let sum = SumCircuit::instance(&[u64::MAX, 1]);
+        let total = sum.total.unwrap();
+        assert_eq!(total, Fr::from(u64::MAX as u128 + 1));
+        assert!(proves_and_verifies(SumCircuit::setup_shape(2), sum, &[total]));
+
+        assert!(proves_and_verifies(FactorCircuit::setup_shape(), FactorCircuit::instance(5, 7), &[Fr::from(35u64)]));
+
+        let output = BitOutput::BitLength;
+        let range = RangeWithBitCircuit::instance(38, 16, output);
+        assert!(proves_and_verifies(RangeWithBitCircuit::setup_shape(16, output), range, &[Fr::from(6u64)]));
+
+        let parity = ParityCircuit::instance(7, 8);
+        assert!(proves_and_verifies(ParityCircuit::setup_shape(8), parity, &[Fr::from(1u64)]));
+
+        let preimage = PoseidonPreimageCircuit::instance(Fr::from(42u64));
+        let hash = preimage.hash.unwrap();
+        assert!(proves_and_verifies(PoseidonPreimageCircuit::setup_shape(), preimage, &[hash]));
+    }
+}
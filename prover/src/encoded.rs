@@ -0,0 +1,144 @@
+// Byte-backed newtypes for the two encodings API consumers pass around most.
+// `CompressedProof` and `UncompressedVk` keep the decoded value next to its bytes:
+// `TryFrom<&[u8]>` decodes and validates once, `AsRef<[u8]>` hands the bytes back
+// unchanged, and `From` encodes an arkworks value.
+
+use ark_bn254::Bn254;
+use ark_groth16::{Proof, VerifyingKey};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError, Validate};
+
+use crate::error::ProverError;
+
+/// A Groth16 proof in the 128-byte compressed encoding `save_proof` and the PVM contract use
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompressedProof {
+    bytes: Vec<u8>,
+    proof: Proof<Bn254>,
+}
+
+impl CompressedProof {
+    pub fn proof(&self) -> &Proof<Bn254> {
+        &self.proof
+    }
+
+    pub fn into_proof(self) -> Proof<Bn254> {
+        self.proof
+    }
+}
+
+/// A verifying key in the uncompressed encoding the PVM contract embeds
+#[derive(Debug, Clone, PartialEq)]
+pub struct UncompressedVk {
+    bytes: Vec<u8>,
+    vk: VerifyingKey<Bn254>,
+}
+
+impl UncompressedVk {
+    pub fn vk(&self) -> &VerifyingKey<Bn254> {
+        &self.vk
+    }
+
+    pub fn into_vk(self) -> VerifyingKey<Bn254> {
+        self.vk
+    }
+}
+
+// Decode with validation, refusing trailing bytes
+fn decode_exact<T: CanonicalDeserialize + CanonicalSerialize>(
+    bytes: &[u8],
+    compress: Compress,
+) -> Result<T, ProverError> {
+    let value = T::deserialize_with_mode(bytes, compress, Validate::Yes)?;
+    if value.serialized_size(compress) != bytes.len() {
+        return Err(SerializationError::InvalidData.into());
+    }
+    Ok(value)
+}
+
+fn encode<T: CanonicalSerialize>(value: &T, compress: Compress) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value
+        .serialize_with_mode(&mut bytes, compress)
+        .expect("serializing to a Vec cannot fail");
+    bytes
+}
+
+impl TryFrom<&[u8]> for CompressedProof {
+    type Error = ProverError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, ProverError> {
+        let proof = decode_exact(bytes, Compress::Yes)?;
+        Ok(CompressedProof { bytes: bytes.to_vec(), proof })
+    }
+}
+
+impl From<&Proof<Bn254>> for CompressedProof {
+    fn from(proof: &Proof<Bn254>) -> Self {
+        CompressedProof { bytes: encode(proof, Compress::Yes), proof: proof.clone() }
+    }
+}
+
+impl AsRef<[u8]> for CompressedProof {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl TryFrom<&[u8]> for UncompressedVk {
+    type Error = ProverError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, ProverError> {
+        let vk = decode_exact(bytes, Compress::No)?;
+        Ok(UncompressedVk { bytes: bytes.to_vec(), vk })
+    }
+}
+
+impl From<&VerifyingKey<Bn254>> for UncompressedVk {
+    fn from(vk: &VerifyingKey<Bn254>) -> Self {
+        UncompressedVk { bytes: encode(vk, Compress::No), vk: vk.clone() }
+    }
+}
+
+impl AsRef<[u8]> for UncompressedVk {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate_proof;
+
+    #[test]
+    fn test_proof_and_vk_bytes_round_trip_through_newtypes() {
+        let (proof, c, pk) = generate_proof(3, 4).expect("proof generation failed");
+
+        let encoded = CompressedProof::from(&proof);
+        assert_eq!(encoded.as_ref().len(), 128);
+        let decoded = CompressedProof::try_from(encoded.as_ref()).expect("decoding proof failed");
+        assert_eq!(decoded, encoded);
+
+        let vk = UncompressedVk::from(&pk.vk);
+        let decoded_vk = UncompressedVk::try_from(vk.as_ref()).expect("decoding vk failed");
+        assert_eq!(decoded_vk.vk(), &pk.vk);
+        assert!(crate::verify_proof(decoded.proof(), c, decoded_vk.vk()).expect("verification failed"));
+    }
+
+    #[test]
+    fn test_newtypes_refuse_truncated_or_padded_bytes() {
+        let (proof, _c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let proof_bytes = CompressedProof::from(&proof).as_ref().to_vec();
+        let vk_bytes = UncompressedVk::from(&pk.vk).as_ref().to_vec();
+
+        assert!(matches!(CompressedProof::try_from(&proof_bytes[..127]), Err(ProverError::Serialization(_))));
+        let mut padded = proof_bytes.clone();
+        padded.push(0);
+        assert!(CompressedProof::try_from(&padded[..]).is_err());
+        // An uncompressed proof is not a compressed one
+        let uncompressed = crate::utils::encode_proof(&proof, crate::utils::ProofEncoding::Uncompressed);
+        assert!(CompressedProof::try_from(&uncompressed[..]).is_err());
+
+        assert!(UncompressedVk::try_from(&vk_bytes[..vk_bytes.len() - 1]).is_err());
+    }
+}
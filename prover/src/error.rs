@@ -0,0 +1,114 @@
+// Error type returned by the prover library.
+// Keeps `anyhow` and `Box<dyn Error>` out of the public API; binaries adapt
+// `ProverError` into whatever error handling they use.
+
+use ark_relations::r1cs::SynthesisError;
+use ark_serialize::SerializationError;
+use std::fmt;
+
+use crate::utils::hex_string;
+
+#[derive(Debug)]
+pub enum ProverError {
+    /// Reading or writing an artefact failed
+    Io(std::io::Error),
+    /// arkworks could not (de)serialize a key, proof or field element
+    Serialization(SerializationError),
+    /// Circuit synthesis, setup, proving or verification failed inside arkworks
+    Synthesis(SynthesisError),
+    /// The pairing check could not run, e.g. the verifying key does not match the public input count
+    Verification(SynthesisError),
+    /// Calldata has the wrong length or layout
+    InvalidCalldata(String),
+    /// A generated key source file could not be parsed
+    InvalidKeyFile(String),
+    /// A curve point is off the curve or outside the prime-order subgroup
+    InvalidPoint(String),
+    /// Text could not be parsed as a BN254 scalar field element
+    InvalidFieldElement(String),
+    /// Hash parameters are inconsistent, e.g. a Poseidon S-box that is not a permutation
+    InvalidConfig(String),
+    /// A saved artefact carries a version tag this build cannot read
+    UnsupportedVersion { found: u8, expected: u8 },
+    /// A proof file's curve or arkworks serialization tag differs from what this build writes,
+    /// e.g. a proof saved by a build on another arkworks release
+    IncompatibleFormat { tag: &'static str, found: u8, expected: u8 },
+    /// A circuit's assignment violates one of its constraints, so no valid proof exists
+    Unsatisfied { constraint: usize },
+    /// A circuit handed to the prover lacks an assignment, e.g. a setup shape
+    MissingWitness { field: &'static str },
+    /// A file's payload has a size no artefact of the expected type can have,
+    /// e.g. a proof file passed where a verifying key was expected
+    WrongArtifactType { artifact: &'static str, expected_size: &'static str, actual_size: usize },
+    /// A verifying key's fingerprint is not the one the caller pinned
+    VkMismatch { expected: [u8; 32], found: [u8; 32] },
+}
+
+impl fmt::Display for ProverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProverError::Io(e) => write!(f, "I/O error: {}", e),
+            ProverError::Serialization(e) => write!(f, "serialization error: {}", e),
+            ProverError::Synthesis(e) => write!(f, "synthesis error: {}", e),
+            ProverError::Verification(e) => write!(f, "verification error: {}", e),
+            ProverError::InvalidCalldata(msg) => write!(f, "invalid calldata: {}", msg),
+            ProverError::InvalidKeyFile(msg) => write!(f, "invalid key file: {}", msg),
+            ProverError::InvalidPoint(msg) => write!(f, "invalid point: {}", msg),
+            ProverError::InvalidFieldElement(msg) => write!(f, "invalid field element: {}", msg),
+            ProverError::InvalidConfig(msg) => write!(f, "invalid configuration: {}", msg),
+            ProverError::UnsupportedVersion { found, expected } => {
+                write!(f, "unsupported artefact version {} (expected {})", found, expected)
+            }
+            ProverError::IncompatibleFormat { tag, found, expected } => {
+                write!(f, "proof has {} tag {}, but this build reads {}", tag, found, expected)
+            }
+            ProverError::Unsatisfied { constraint } => {
+                write!(f, "circuit assignment violates constraint {}", constraint)
+            }
+            ProverError::MissingWitness { field } => {
+                write!(f, "circuit has no assignment for `{}`; prove an `instance`, not a `setup_shape`", field)
+            }
+            ProverError::WrongArtifactType { artifact, expected_size, actual_size } => write!(
+                f,
+                "a {}-byte payload is not a {} ({} bytes expected)",
+                actual_size, artifact, expected_size
+            ),
+            ProverError::VkMismatch { expected, found } => write!(
+                f,
+                "verifying key fingerprint 0x{} does not match the pinned 0x{}",
+                hex_string(found),
+                hex_string(expected)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ProverError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ProverError::Io(e) => Some(e),
+            ProverError::Serialization(e) => Some(e),
+            ProverError::Synthesis(e) => Some(e),
+            ProverError::Verification(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ProverError {
+    fn from(e: std::io::Error) -> Self {
+        ProverError::Io(e)
+    }
+}
+
+impl From<SerializationError> for ProverError {
+    fn from(e: SerializationError) -> Self {
+        ProverError::Serialization(e)
+    }
+}
+
+impl From<SynthesisError> for ProverError {
+    fn from(e: SynthesisError) -> Self {
+        ProverError::Synthesis(e)
+    }
+}
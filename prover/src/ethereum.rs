@@ -0,0 +1,204 @@
+// Ethereum byte layout for BN254 points and verifying keys.
+// The EVM precompiles expect big-endian 32-byte coordinates and G2 points with
+// their Fq2 components in [c1, c0] order, the reverse of arkworks' [c0, c1].
+//
+// Verifying key layout:
+// - alpha_g1 (64) | beta_g2 (128) | gamma_g2 (128) | delta_g2 (128)
+// - gamma_abc_g1[i] (64 each), as many as remain
+// The point at infinity is encoded as all zeros.
+// `vk_bytes_to_arkworks` turns either layout into the uncompressed arkworks bytes
+// the PVM contract embeds.
+// `estimate_eth_verify_gas` prices a Solidity Groth16 verification by its precompile calls.
+
+use ark_bn254::{Bn254, Fq, Fq2, G1Affine, G2Affine};
+use ark_ff::{BigInteger, PrimeField, Zero};
+use ark_groth16::VerifyingKey;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+
+use crate::error::ProverError;
+
+pub const FQ_LEN: usize = 32;
+pub const G1_LEN: usize = 2 * FQ_LEN;
+pub const G2_LEN: usize = 4 * FQ_LEN;
+
+// alpha + beta + gamma + delta
+const VK_FIXED_LEN: usize = G1_LEN + 3 * G2_LEN;
+
+// Precompile gas since Istanbul (EIP-1108)
+/// ecAdd (0x06)
+pub const EC_ADD_GAS: u64 = 150;
+/// ecMul (0x07)
+pub const EC_MUL_GAS: u64 = 6_000;
+/// ecPairing (0x08): fixed part
+pub const PAIRING_BASE_GAS: u64 = 45_000;
+/// ecPairing (0x08): per (G1, G2) pair
+pub const PAIRING_PER_PAIR_GAS: u64 = 34_000;
+
+// e(-A, B) · e(alpha, beta) · e(vk_x, gamma) · e(C, delta) == 1
+const GROTH16_PAIRS: u64 = 4;
+
+/// Gas of the precompile calls a Solidity Groth16 verifier makes for `num_public_inputs`:
+/// one ecMul and one ecAdd per input to fold vk_x = IC[0] + Σ input_i · IC[i+1], then a
+/// single four-pair pairing check. The 21000 intrinsic transaction cost, calldata and the
+/// verifier's own bytecode come on top, so budget some headroom over this figure.
+pub fn estimate_eth_verify_gas(num_public_inputs: usize) -> u64 {
+    let inputs = num_public_inputs as u64;
+    PAIRING_BASE_GAS + GROTH16_PAIRS * PAIRING_PER_PAIR_GAS + inputs * (EC_MUL_GAS + EC_ADD_GAS)
+}
+
+/// Encode a verifying key in Ethereum order
+pub fn vk_to_ethereum_bytes(vk: &VerifyingKey<Bn254>) -> Vec<u8> {
+    let mut out = Vec::with_capacity(VK_FIXED_LEN + vk.gamma_abc_g1.len() * G1_LEN);
+    out.extend_from_slice(&g1_to_ethereum_bytes(&vk.alpha_g1));
+    out.extend_from_slice(&g2_to_ethereum_bytes(&vk.beta_g2));
+    out.extend_from_slice(&g2_to_ethereum_bytes(&vk.gamma_g2));
+    out.extend_from_slice(&g2_to_ethereum_bytes(&vk.delta_g2));
+    for point in &vk.gamma_abc_g1 {
+        out.extend_from_slice(&g1_to_ethereum_bytes(point));
+    }
+    out
+}
+
+/// Decode a verifying key from Ethereum order, checking every point
+pub fn vk_from_ethereum_bytes(bytes: &[u8]) -> Result<VerifyingKey<Bn254>, ProverError> {
+    if bytes.len() < VK_FIXED_LEN + G1_LEN || !(bytes.len() - VK_FIXED_LEN).is_multiple_of(G1_LEN) {
+        return Err(ProverError::Serialization(SerializationError::InvalidData));
+    }
+
+    let (alpha, rest) = bytes.split_at(G1_LEN);
+    let (beta, rest) = rest.split_at(G2_LEN);
+    let (gamma, rest) = rest.split_at(G2_LEN);
+    let (delta, rest) = rest.split_at(G2_LEN);
+
+    Ok(VerifyingKey {
+        alpha_g1: g1_from_ethereum_bytes(alpha)?,
+        beta_g2: g2_from_ethereum_bytes(beta)?,
+        gamma_g2: g2_from_ethereum_bytes(gamma)?,
+        delta_g2: g2_from_ethereum_bytes(delta)?,
+        gamma_abc_g1: rest
+            .chunks(G1_LEN)
+            .map(g1_from_ethereum_bytes)
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+/// Byte layout of a serialized verifying key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VkByteOrder {
+    /// arkworks' uncompressed `CanonicalSerialize` output, as embedded in the PVM contract
+    #[default]
+    Arkworks,
+    /// The layout written by `vk_to_ethereum_bytes`
+    Ethereum,
+}
+
+/// Re-encode verifying key bytes of either layout as uncompressed arkworks bytes
+pub fn vk_bytes_to_arkworks(bytes: &[u8], order: VkByteOrder) -> Result<Vec<u8>, ProverError> {
+    match order {
+        VkByteOrder::Arkworks => Ok(bytes.to_vec()),
+        VkByteOrder::Ethereum => {
+            let mut out = Vec::new();
+            vk_from_ethereum_bytes(bytes)?.serialize_uncompressed(&mut out)?;
+            Ok(out)
+        }
+    }
+}
+
+pub fn g1_to_ethereum_bytes(p: &G1Affine) -> [u8; G1_LEN] {
+    let mut out = [0u8; G1_LEN];
+    if !p.infinity {
+        out[..FQ_LEN].copy_from_slice(&fq_to_be(&p.x));
+        out[FQ_LEN..].copy_from_slice(&fq_to_be(&p.y));
+    }
+    out
+}
+
+pub fn g2_to_ethereum_bytes(p: &G2Affine) -> [u8; G2_LEN] {
+    let mut out = [0u8; G2_LEN];
+    if !p.infinity {
+        out[..FQ_LEN].copy_from_slice(&fq_to_be(&p.x.c1));
+        out[FQ_LEN..2 * FQ_LEN].copy_from_slice(&fq_to_be(&p.x.c0));
+        out[2 * FQ_LEN..3 * FQ_LEN].copy_from_slice(&fq_to_be(&p.y.c1));
+        out[3 * FQ_LEN..].copy_from_slice(&fq_to_be(&p.y.c0));
+    }
+    out
+}
+
+pub fn g1_from_ethereum_bytes(bytes: &[u8]) -> Result<G1Affine, ProverError> {
+    let x = fq_from_be(&bytes[..FQ_LEN])?;
+    let y = fq_from_be(&bytes[FQ_LEN..G1_LEN])?;
+    if x.is_zero() && y.is_zero() {
+        return Ok(G1Affine::identity());
+    }
+    let p = G1Affine::new_unchecked(x, y);
+    if !p.is_on_curve() || !p.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(ProverError::InvalidPoint("G1 point is not on the curve".to_string()));
+    }
+    Ok(p)
+}
+
+pub fn g2_from_ethereum_bytes(bytes: &[u8]) -> Result<G2Affine, ProverError> {
+    let x = Fq2::new(fq_from_be(&bytes[FQ_LEN..2 * FQ_LEN])?, fq_from_be(&bytes[..FQ_LEN])?);
+    let y = Fq2::new(fq_from_be(&bytes[3 * FQ_LEN..G2_LEN])?, fq_from_be(&bytes[2 * FQ_LEN..3 * FQ_LEN])?);
+    if x.is_zero() && y.is_zero() {
+        return Ok(G2Affine::identity());
+    }
+    let p = G2Affine::new_unchecked(x, y);
+    if !p.is_on_curve() || !p.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(ProverError::InvalidPoint("G2 point is not on the curve or not in the prime-order subgroup".to_string()));
+    }
+    Ok(p)
+}
+
+fn fq_to_be(f: &Fq) -> [u8; FQ_LEN] {
+    let mut out = [0u8; FQ_LEN];
+    out.copy_from_slice(&f.into_bigint().to_bytes_be());
+    out
+}
+
+// Rejects values >= the modulus
+fn fq_from_be(bytes: &[u8]) -> Result<Fq, ProverError> {
+    let mut le = bytes.to_vec();
+    le.reverse();
+    Ok(Fq::deserialize_uncompressed(&le[..])?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate_proof;
+
+    #[test]
+    fn test_vk_ethereum_bytes_round_trip() {
+        let (_proof, _c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let vk = pk.vk;
+
+        let bytes = vk_to_ethereum_bytes(&vk);
+        assert_eq!(bytes.len(), VK_FIXED_LEN + vk.gamma_abc_g1.len() * G1_LEN);
+
+        // beta_g2.x is stored as [c1, c0], big-endian
+        assert_eq!(&bytes[G1_LEN..G1_LEN + FQ_LEN], &fq_to_be(&vk.beta_g2.x.c1));
+        assert_eq!(&bytes[G1_LEN + FQ_LEN..G1_LEN + 2 * FQ_LEN], &fq_to_be(&vk.beta_g2.x.c0));
+
+        let decoded = vk_from_ethereum_bytes(&bytes).expect("decoding failed");
+        assert_eq!(decoded.beta_g2, vk.beta_g2);
+        assert_eq!(decoded.gamma_g2, vk.gamma_g2);
+        assert_eq!(decoded.delta_g2, vk.delta_g2);
+        assert_eq!(decoded, vk);
+    }
+
+    #[test]
+    fn test_truncated_ethereum_vk_is_rejected() {
+        let (_proof, _c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let bytes = vk_to_ethereum_bytes(&pk.vk);
+        assert!(vk_from_ethereum_bytes(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_single_input_verify_gas_estimate() {
+        // 45000 + 4 * 34000 for the pairing, 6000 + 150 to fold the one input
+        assert_eq!(estimate_eth_verify_gas(1), 187_150);
+        assert_eq!(estimate_eth_verify_gas(0), 181_000);
+        assert_eq!(estimate_eth_verify_gas(3) - estimate_eth_verify_gas(2), EC_MUL_GAS + EC_ADD_GAS);
+    }
+}
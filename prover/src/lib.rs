@@ -1,77 +1,1196 @@
 // Core zkSNARK logic for proof generation, verification, and key export.
 // Designed for use across CLI, tests, and embedded smart contract targets.
+// Setup and proving go through ark-snark's `SNARK` trait (`circuit_specific_setup`,
+// `prove`). For `Groth16<Bn254>` that uses the default `LibsnarkReduction` R1CS-to-QAP
+// map, so keys and proofs are interchangeable with arkworks' `*_with_reduction` helpers.
 
 // Includes:
+// - `setup_circuit_specific`: circuit-specific Groth16 setup through the `SNARK` trait
 // - `generate_proof`: produces a Groth16 proof and public output for a * b = c
+// - `generate_nonzero_proof`: as above, additionally proving a != 0 and b != 0
+// - `prove_with_key`: proves a * b = c under an existing proving key
+// - `prove_many_parallel`: proves many a * b = c pairs under one key in parallel (`parallel` feature)
+// - `ProvingSession`: holds one proving key for a long-running service proving a * b = c per request
+// - `prove_circuit`: proves any circuit under an existing key, refusing one with missing assignments
+// - `prove_sum`: produces a Groth16 proof and public total for sum(xs) = total
+// - `prove_weighted_sum`: as above, for sum(ws[i] * xs[i]) = total with public weights
+// - `prove_factorization`: produces a Groth16 proof of knowing factors p, q > 1 of public n
+// - `prove_mod`: produces a Groth16 proof that public x leaves the public remainder r modulo public m
+// - `prove_parity`: produces a Groth16 proof of the parity of a hidden u64
+// - `prove_hash_preimage`: produces a Groth16 proof of knowing a secret with a public Poseidon hash
+// - `prove_hash_output`: as above, with the hash computed in-circuit and returned as its public output
+// - `check_satisfied`: reports the first constraint a circuit's assignment violates, before proving
+// - `parse_fr`: parses a decimal or 0x-hex field element
 // - `verify_proof`: checks validity of a proof against a verifying key
-// - `export_verifying_key_to_rs`: outputs verifying key as a Rust byte array for embedding
-// - `load_verifying_key_from_file`: loads a verifying key from a binary file
+// - `verify_proof_u64`: as above, taking the public output of a * b = c as a u64
+// - `verify_proof_pinned`: as above, first refusing a key whose fingerprint is not the expected one
+// - `verify_with_prepared`: as above, against a prepared verifying key loaded from disk
+// - `verify_proof_with_inputs`: as above, for any number of public inputs, including none
+// - `rerandomize_proof`: produces a fresh, unlinkable proof of the same statement
+// - `find_correct_public_input`: finds which candidate public-input vector a proof verifies against
+// - `verify_against_any`: finds which candidate verifying key a proof verifies against
+// - `verify_proof_bytes`: panic-free verification over raw, untrusted bytes
+// - `decode_and_verify`: as above, also returning the decoded public inputs
+// - `prepare_and_verify`: verification of the proof, input and key payloads saved by `utils`
+// - `build_pvm_calldata` / `verify_pvm_calldata`: the PVM contract's calldata layout, built and checked off-chain
+// - `export_verifying_key_to_rs`: outputs verifying key as a Rust byte array (and its length) for embedding
+// - `export_verifying_key_to_ts`: outputs the same bytes as a TypeScript `Uint8Array` for frontends
+// - `parse_verifying_key_rs_source`: reads the byte array back out of an exported source file
+// - `vk_fingerprint`: SHA-256 identifying a verifying key
+// - `vk_equal` / `vk_diff`: compares two verifying keys point by point
+// - `load_verifying_key_from_file`: loads a versioned verifying key file written by `utils::save_verifying_key`
+// - `error`: `ProverError`, the error type of every fallible function in this crate
+// - `bundle`: `ProofBundle`, a proof with its public input and verifying key as one artefact
+// - `cache`: `VerifierCache`, a bounded LRU of verification results for repeated proofs
+// - `calldata`: decoding, verification and diagnosis of generated calldata
+// - `encoded`: `CompressedProof` / `UncompressedVk`, byte-backed newtypes with `TryFrom<&[u8]>`
+// - `ethereum`: Ethereum-ordered (big-endian, [c1, c0] G2) encoding of points and verifying keys
+// - `poseidon`: Poseidon sponge hash over BN254 scalars, natively and in-circuit, and of byte strings
+// - `prepared`: `PreparedMulProver`, repeated `MulCircuit` proving with cached constraint matrices,
+//   from synthesized or precomputed assignments
+// - `r1cs`: `export_r1cs_json`, a circuit's constraint matrices (and optionally its assignment) as sparse JSON
+// - `signature`: Schnorr-style Poseidon key pairs and signing, verified by `PoseidonSignatureCircuit`
+// - `solidity`: Foundry test fixture calling a deployed verifier with generated calldata
+// - `summary`: concise `Display` of a proof for logs, and `serialized_sizes` of each artefact
+// - `test_utils`: round-trip assertions and `corrupt_proof` for tests (`test-utils` feature)
 
+pub mod bundle;
+pub mod cache;
+pub mod calldata;
 pub mod circuit;
+pub mod encoded;
+pub mod error;
+pub mod ethereum;
+pub mod poseidon;
+pub mod prepared;
+pub mod r1cs;
+pub mod signature;
+pub mod solidity;
+pub mod summary;
 pub mod utils;
 
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+
 use ark_bn254::{Bn254, Fr};
-use ark_groth16::{Groth16, Proof, ProvingKey, VerifyingKey, prepare_verifying_key};
+use ark_ff::{BigInt, PrimeField};
+use ark_groth16::{Groth16, PreparedVerifyingKey, Proof, ProvingKey, VerifyingKey, prepare_verifying_key};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem, SynthesisError};
+use ark_snark::SNARK;
 use rand::thread_rng;
+use sha2::{Digest, Sha256};
 use std::fs::{self, File};
-use std::io::{BufReader, Write};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::circuit::{
+    Assignment, FactorCircuit, ModCircuit, MulCircuit, ParityCircuit, PoseidonHashOutputCircuit,
+    PoseidonPreimageCircuit, SumCircuit, WeightedSumCircuit,
+};
+pub use crate::error::ProverError;
+
+/// Proof, public output, and the proving key it was generated with
+pub type GeneratedProof = (Proof<Bn254>, Fr, ProvingKey<Bn254>);
 
-use crate::circuit::MulCircuit;
+/// As `GeneratedProof`, for circuits with several public inputs, in allocation order
+pub type GeneratedProofWithInputs = (Proof<Bn254>, Vec<Fr>, ProvingKey<Bn254>);
+
+/// Circuit-specific Groth16 setup through the `SNARK` trait, as every prover in this crate runs it.
+/// For `Groth16<Bn254>` this is `generate_random_parameters_with_reduction` under the default
+/// `LibsnarkReduction`, so the keys have the same shape and size either way: the proving key
+/// grows with the constraint and variable counts, the verifying key with the public inputs only.
+/// The trait path buys interchangeability with other `SNARK` implementations, not smaller keys.
+///
+/// Pass a circuit's `setup_shape`. Setup synthesizes in arkworks' setup mode, which never
+/// evaluates assignments, so an assigned `instance` yields a key of the same shape and leaks
+/// nothing into it; but handing a witness to the setup party is needless exposure, and the
+/// all-`None` shape makes it impossible.
+pub fn setup_circuit_specific<C: ConstraintSynthesizer<Fr>>(
+    circuit: C,
+) -> Result<(ProvingKey<Bn254>, VerifyingKey<Bn254>), ProverError> {
+    Ok(Groth16::<Bn254>::circuit_specific_setup(circuit, &mut thread_rng())?)
+}
 
 /// Generate a Groth16 proof for a * b = c
-pub fn generate_proof(a: u64, b: u64) -> Result<(Proof<Bn254>, Fr, ProvingKey<Bn254>), Box<dyn std::error::Error>> {
+pub fn generate_proof(a: u64, b: u64) -> Result<GeneratedProof, ProverError> {
+    generate_mul_proof(a, b, false)
+}
+
+/// Generate a Groth16 proof for a * b = c that additionally proves a != 0 and b != 0
+pub fn generate_nonzero_proof(a: u64, b: u64) -> Result<GeneratedProof, ProverError> {
+    generate_mul_proof(a, b, true)
+}
+
+fn generate_mul_proof(a: u64, b: u64, nonzero: bool) -> Result<GeneratedProof, ProverError> {
     let mut rng = thread_rng();
 
-    let a_fr = Fr::from(a);
-    let b_fr = Fr::from(b);
-    let c = a_fr * b_fr;
+    // A zero factor violates the nonzero constraints; refuse it before paying for setup
+    if nonzero {
+        check_satisfied(MulCircuit { nonzero, ..MulCircuit::instance(a, b) })?;
+    }
+    let circuit = MulCircuit { nonzero, ..MulCircuit::setup_shape() };
+    let (pk, _) = Groth16::<Bn254>::circuit_specific_setup(circuit, &mut rng)?;
+
+    let (proof, c) = prove_with_key(&pk, a, b, nonzero)?;
+    Ok((proof, c, pk))
+}
+
+/// Prove a * b = c under an existing proving key, re-synthesizing the circuit.
+/// For many proofs under one key, `prepared::PreparedMulProver` skips the redundant setup work.
+pub fn prove_with_key(
+    pk: &ProvingKey<Bn254>,
+    a: u64,
+    b: u64,
+    nonzero: bool,
+) -> Result<(Proof<Bn254>, Fr), ProverError> {
+    let instance = MulCircuit { nonzero, ..MulCircuit::instance(a, b) };
+    let c = instance.c.expect("instance assigns c");
+
+    let proof = prove_circuit(pk, instance)?;
+    Ok((proof, c))
+}
+
+/// Prove a * b = c for every pair under one proving key, spread over rayon's thread pool.
+/// Each proof draws its blinding factors from `thread_rng`, which is thread-local and
+/// seeded from OS entropy per thread, so no RNG state is shared between workers.
+#[cfg(feature = "parallel")]
+pub fn prove_many_parallel(pk: &ProvingKey<Bn254>, pairs: &[(u64, u64)]) -> Result<Vec<(Proof<Bn254>, Fr)>, ProverError> {
+    use rayon::prelude::*;
+
+    pairs.par_iter().map(|&(a, b)| prove_with_key(pk, a, b, false)).collect()
+}
+
+/// A proving key held for the lifetime of a proving service, so requests skip reloading it.
+/// Blinding factors come from `thread_rng`, so a session shared across threads needs no lock.
+pub struct ProvingSession {
+    pk: ProvingKey<Bn254>,
+}
+
+impl ProvingSession {
+    pub fn new(pk: ProvingKey<Bn254>) -> Self {
+        ProvingSession { pk }
+    }
+
+    /// The key proofs from this session verify against
+    pub fn verifying_key(&self) -> &VerifyingKey<Bn254> {
+        &self.pk.vk
+    }
+
+    /// Prove a * b = c, returning the proof and c
+    pub fn prove(&self, a: u64, b: u64) -> Result<(Proof<Bn254>, Fr), ProverError> {
+        prove_with_key(&self.pk, a, b, false)
+    }
+}
+
+/// Prove any fully assigned circuit under `pk`.
+/// A circuit missing an assignment (such as a `setup_shape`) is refused with
+/// `ProverError::MissingWitness` naming the field, and one whose assignment violates a
+/// constraint with `ProverError::Unsatisfied`, before arkworks proves it.
+pub fn prove_circuit<C: ConstraintSynthesizer<Fr> + Assignment + Clone>(
+    pk: &ProvingKey<Bn254>,
+    circuit: C,
+) -> Result<Proof<Bn254>, ProverError> {
+    if let Some(field) = circuit.missing_assignment() {
+        return Err(ProverError::MissingWitness { field });
+    }
+    // Groth16 proving only debug-asserts satisfiability: a panic in debug builds, a useless proof in release
+    check_satisfied(circuit.clone())?;
+    Ok(Groth16::<Bn254>::prove(pk, circuit, &mut thread_rng())?)
+}
+
+/// Generate a Groth16 proof for sum(xs) = total
+pub fn prove_sum(xs: &[u64]) -> Result<GeneratedProof, ProverError> {
+    let mut rng = thread_rng();
+
+    let (pk, _) = Groth16::<Bn254>::circuit_specific_setup(SumCircuit::setup_shape(xs.len()), &mut rng)?;
+
+    let instance = SumCircuit::instance(xs);
+    let total = instance.total.expect("instance assigns total");
+
+    let proof = Groth16::<Bn254>::prove(&pk, instance, &mut rng)?;
+    Ok((proof, total, pk))
+}
+
+/// Generate a Groth16 proof for sum(weights[i] * xs[i]) = total. The proof's public
+/// inputs are the weights followed by the returned total; verify it with
+/// `verify_proof_with_inputs`. Slices of different lengths give `InvalidConfig`.
+pub fn prove_weighted_sum(weights: &[u64], xs: &[u64]) -> Result<GeneratedProof, ProverError> {
+    if weights.len() != xs.len() {
+        return Err(ProverError::InvalidConfig(format!("{} weights for {} terms", weights.len(), xs.len())));
+    }
+    let mut rng = thread_rng();
+
+    let (pk, _) = Groth16::<Bn254>::circuit_specific_setup(WeightedSumCircuit::setup_shape(xs.len()), &mut rng)?;
+
+    let instance = WeightedSumCircuit::instance(weights, xs);
+    let total = instance.total.expect("instance assigns total");
+
+    let proof = Groth16::<Bn254>::prove(&pk, instance, &mut rng)?;
+    Ok((proof, total, pk))
+}
+
+/// Generate a Groth16 proof of knowing factors p, q > 1 of n = p * q
+pub fn prove_factorization(p: u64, q: u64) -> Result<GeneratedProof, ProverError> {
+    let mut rng = thread_rng();
+
+    // Groth16 proving only debug-asserts the assignment; p or q <= 1 would panic or yield a useless proof
+    check_satisfied(FactorCircuit::instance(p, q))?;
+    let (pk, _) = Groth16::<Bn254>::circuit_specific_setup(FactorCircuit::setup_shape(), &mut rng)?;
+
+    let instance = FactorCircuit::instance(p, q);
+    let n = instance.n.expect("instance assigns n");
+
+    let proof = Groth16::<Bn254>::prove(&pk, instance, &mut rng)?;
+    Ok((proof, n, pk))
+}
+
+/// Generate a Groth16 proof that x mod m = r, returned with its public inputs [x, m, r]
+pub fn prove_mod(x: u64, m: u64) -> Result<GeneratedProofWithInputs, ProverError> {
+    if m == 0 {
+        return Err(ProverError::InvalidConfig("modulus must be non-zero".to_string()));
+    }
+    let mut rng = thread_rng();
+    let num_bits = u64::BITS as usize;
+
+    let (pk, _) = Groth16::<Bn254>::circuit_specific_setup(ModCircuit::setup_shape(num_bits), &mut rng)?;
+
+    let instance = ModCircuit::instance(x, m, num_bits);
+    let inputs = vec![Fr::from(x), Fr::from(m), instance.r.expect("instance assigns r")];
+    let proof = Groth16::<Bn254>::prove(&pk, instance, &mut rng)?;
+    Ok((proof, inputs, pk))
+}
+
+/// Generate a Groth16 proof that a hidden `value` is even (public 0) or odd (public 1)
+pub fn prove_parity(value: u64) -> Result<GeneratedProof, ProverError> {
+    let mut rng = thread_rng();
+    let num_bits = u64::BITS as usize;
+    let parity = Fr::from(value & 1);
+
+    let (pk, _) = Groth16::<Bn254>::circuit_specific_setup(ParityCircuit::setup_shape(num_bits), &mut rng)?;
+
+    let instance = ParityCircuit::instance(value, num_bits);
+    let proof = Groth16::<Bn254>::prove(&pk, instance, &mut rng)?;
+    Ok((proof, parity, pk))
+}
+
+/// Generate a Groth16 proof of knowing `secret` such that poseidon_sponge_hash([secret]) = hash
+pub fn prove_hash_preimage(secret: Fr) -> Result<GeneratedProof, ProverError> {
+    let mut rng = thread_rng();
+
+    let (pk, _) = Groth16::<Bn254>::circuit_specific_setup(PoseidonPreimageCircuit::setup_shape(), &mut rng)?;
+
+    let instance = PoseidonPreimageCircuit::instance(secret);
+    let hash = instance.hash.expect("instance assigns hash");
+    let proof = Groth16::<Bn254>::prove(&pk, instance, &mut rng)?;
+    Ok((proof, hash, pk))
+}
+
+/// Generate a Groth16 proof of knowing `secret`, returning the Poseidon hash it exposes.
+/// The hash is read from the synthesized instance, i.e. computed by the circuit itself.
+pub fn prove_hash_output(secret: Fr) -> Result<GeneratedProof, ProverError> {
+    let mut rng = thread_rng();
+
+    let (pk, _) = Groth16::<Bn254>::circuit_specific_setup(PoseidonHashOutputCircuit::setup_shape(), &mut rng)?;
+
+    let instance = PoseidonHashOutputCircuit::instance(secret);
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    instance.clone().generate_constraints(cs.clone())?;
+    // instance_assignment[0] is the constant one
+    let hash = cs.borrow().ok_or(SynthesisError::MissingCS)?.instance_assignment[1];
+
+    let proof = Groth16::<Bn254>::prove(&pk, instance, &mut rng)?;
+    Ok((proof, hash, pk))
+}
+
+/// Synthesize `circuit` with its assignment and check every constraint.
+/// Setup never evaluates assignments and proving only debug-asserts them, so an unsatisfiable
+/// instance otherwise panics in debug builds and yields a proof that fails verification in release.
+pub fn check_satisfied<C: ConstraintSynthesizer<Fr>>(circuit: C) -> Result<(), ProverError> {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone())?;
+    cs.finalize();
 
-    let circuit = MulCircuit { a: None, b: None, c: None };
-    let pk = Groth16::<Bn254>::generate_random_parameters_with_reduction(circuit, &mut rng)?;
+    let matrices = cs.to_matrices().ok_or(SynthesisError::MissingCS)?;
+    let cs = cs.borrow().ok_or(SynthesisError::MissingCS)?;
+    let assignment = [cs.instance_assignment.as_slice(), cs.witness_assignment.as_slice()].concat();
+    let eval = |row: &[(Fr, usize)]| -> Fr { row.iter().map(|(coeff, var)| *coeff * assignment[*var]).sum() };
 
-    let instance = MulCircuit {
-        a: Some(a_fr),
-        b: Some(b_fr),
-        c: Some(c),
+    for constraint in 0..matrices.num_constraints {
+        if eval(&matrices.a[constraint]) * eval(&matrices.b[constraint]) != eval(&matrices.c[constraint]) {
+            return Err(ProverError::Unsatisfied { constraint });
+        }
+    }
+    Ok(())
+}
+
+/// Parse a field element written in decimal or as `0x`-prefixed hex.
+/// Values at or above the modulus are rejected rather than reduced.
+pub fn parse_fr(text: &str) -> Result<Fr, ProverError> {
+    let text = text.trim();
+    let (digits, radix) = match text.strip_prefix("0x") {
+        Some(hex) => (hex, 16),
+        None => (text, 10),
     };
+    (!digits.is_empty())
+        .then(|| parse_limbs(digits, radix))
+        .flatten()
+        .and_then(|limbs| Fr::from_bigint(BigInt::new(limbs)))
+        .ok_or_else(|| ProverError::InvalidFieldElement(format!("`{}` is not below the BN254 scalar modulus", text)))
+}
 
-    let proof = Groth16::<Bn254>::create_random_proof_with_reduction(instance, &pk, &mut rng)?;
-    Ok((proof, c, pk))
+// Little-endian u64 limbs of a digit string, or None on a bad digit or more than 256 bits
+fn parse_limbs(digits: &str, radix: u32) -> Option<[u64; 4]> {
+    let mut limbs = [0u64; 4];
+    for c in digits.chars() {
+        let mut carry = c.to_digit(radix)? as u128;
+        for limb in limbs.iter_mut() {
+            let v = *limb as u128 * radix as u128 + carry;
+            *limb = v as u64;
+            carry = v >> 64;
+        }
+        if carry != 0 {
+            return None;
+        }
+    }
+    Some(limbs)
 }
 
-/// Verify a Groth16 proof against public input c
-pub fn verify_proof(proof: &Proof<Bn254>, c: Fr, vk: &VerifyingKey<Bn254>) -> Result<bool, Box<dyn std::error::Error>> {
+/// Verify a Groth16 proof against public input c.
+/// A wrong or structurally invalid proof gives `Ok(false)`; a verifying key the
+/// pairing check cannot use gives `ProverError::Verification`.
+pub fn verify_proof(proof: &Proof<Bn254>, c: Fr, vk: &VerifyingKey<Bn254>) -> Result<bool, ProverError> {
+    verify_with_prepared(proof, c, &prepare_verifying_key(vk))
+}
+
+/// `verify_proof` for a public output given as a u64, mirroring `generate_proof(a, b)`
+pub fn verify_proof_u64(proof: &Proof<Bn254>, c: u64, vk: &VerifyingKey<Bn254>) -> Result<bool, ProverError> {
+    verify_proof(proof, Fr::from(c), vk)
+}
+
+/// `verify_proof`, refusing with `ProverError::VkMismatch` a key whose `vk_fingerprint`
+/// is not `expected_fingerprint`, e.g. because the wrong key file was loaded
+pub fn verify_proof_pinned(
+    proof: &Proof<Bn254>,
+    c: Fr,
+    vk: &VerifyingKey<Bn254>,
+    expected_fingerprint: [u8; 32],
+) -> Result<bool, ProverError> {
+    let found = vk_fingerprint(vk);
+    if found != expected_fingerprint {
+        return Err(ProverError::VkMismatch { expected: expected_fingerprint, found });
+    }
+    verify_proof(proof, c, vk)
+}
+
+/// `verify_proof` against an already prepared verifying key, skipping preparation
+pub fn verify_with_prepared(
+    proof: &Proof<Bn254>,
+    c: Fr,
+    pvk: &PreparedVerifyingKey<Bn254>,
+) -> Result<bool, ProverError> {
+    if !proof_points_are_valid(proof) {
+        return Ok(false);
+    }
+    Groth16::<Bn254>::verify_proof(pvk, proof, &[c]).map_err(ProverError::Verification)
+}
+
+// A, B and C lie on the curve and in the prime-order subgroup
+pub(crate) fn proof_points_are_valid(proof: &Proof<Bn254>) -> bool {
+    proof.a.is_on_curve()
+        && proof.a.is_in_correct_subgroup_assuming_on_curve()
+        && proof.b.is_on_curve()
+        && proof.b.is_in_correct_subgroup_assuming_on_curve()
+        && proof.c.is_on_curve()
+        && proof.c.is_in_correct_subgroup_assuming_on_curve()
+}
+
+/// `verify_proof` for a circuit with any number of public inputs, in allocation order.
+/// A circuit without public inputs (a pure satisfiability proof) has a key with one
+/// `gamma_abc_g1` entry and verifies against an empty slice.
+pub fn verify_proof_with_inputs(
+    proof: &Proof<Bn254>,
+    inputs: &[Fr],
+    vk: &VerifyingKey<Bn254>,
+) -> Result<bool, ProverError> {
+    if !proof_points_are_valid(proof) {
+        return Ok(false);
+    }
+    Groth16::<Bn254>::verify_proof(&prepare_verifying_key(vk), proof, inputs).map_err(ProverError::Verification)
+}
+
+/// Re-randomize a proof so the same statement yields an unlinkable proof:
+/// A' = A/r1, B' = r1·B + r1·r2·δ, C' = C + r2·A for fresh nonzero r1, r2.
+/// The result verifies against the same verifying key and public inputs.
+pub fn rerandomize_proof(proof: &Proof<Bn254>, vk: &VerifyingKey<Bn254>) -> Proof<Bn254> {
+    Groth16::<Bn254>::rerandomize_proof(vk, proof, &mut thread_rng())
+}
+
+/// Debug helper: try each candidate public-input vector and return the index of the
+/// first one the proof verifies against. Candidates of the wrong length never match.
+pub fn find_correct_public_input(
+    proof: &Proof<Bn254>,
+    vk: &VerifyingKey<Bn254>,
+    candidates: &[Vec<Fr>],
+) -> Option<usize> {
     let pvk = prepare_verifying_key(vk);
-    let result = Groth16::<Bn254>::verify_proof(&pvk, proof, &[c])?;
-    Ok(result)
+    candidates
+        .iter()
+        .position(|inputs| Groth16::<Bn254>::verify_proof(&pvk, proof, inputs).unwrap_or(false))
+}
+
+/// Routing helper for relayers serving several circuits: return the index of the first
+/// verifying key the proof verifies against. Keys expecting a different input count never match.
+pub fn verify_against_any(proof: &Proof<Bn254>, inputs: &[Fr], vks: &[VerifyingKey<Bn254>]) -> Option<usize> {
+    if !proof_points_are_valid(proof) {
+        return None;
+    }
+    vks.iter().position(|vk| {
+        Groth16::<Bn254>::verify_proof(&prepare_verifying_key(vk), proof, inputs).unwrap_or(false)
+    })
 }
 
-/// Export verifying key to a byte array source file for on-chain embedding
-pub fn export_verifying_key_to_rs(vk: &VerifyingKey<Bn254>) -> Result<(), Box<dyn std::error::Error>> {
-    fs::create_dir_all("../keys")?;
+/// Verify untrusted bytes: uncompressed VK (as embedded in the PVM contract),
+/// compressed proof, compressed (little-endian) public input.
+/// Never panics; any decoding failure is reported as `false`. Bytes after a complete
+/// key or proof are not read.
+pub fn verify_proof_bytes(vk_bytes: &[u8], proof_bytes: &[u8], input_bytes: &[u8]) -> bool {
+    matches!(decode_and_verify(vk_bytes, proof_bytes, input_bytes), Ok((true, _)))
+}
+
+/// `verify_proof_bytes` that also returns the public inputs it decoded, e.g. to log them
+/// alongside the result. `inputs_bytes` holds one compressed 32-byte element per input.
+/// Undecodable bytes, or an input count the key does not expect, are an error.
+pub fn decode_and_verify(
+    vk_bytes: &[u8],
+    proof_bytes: &[u8],
+    inputs_bytes: &[u8],
+) -> Result<(bool, Vec<Fr>), ProverError> {
+    let vk = VerifyingKey::<Bn254>::deserialize_uncompressed(vk_bytes)?;
+    let proof = Proof::<Bn254>::deserialize_compressed(proof_bytes)?;
+    if !inputs_bytes.len().is_multiple_of(32) {
+        return Err(ProverError::InvalidFieldElement(format!(
+            "{} input bytes are not a whole number of 32-byte elements",
+            inputs_bytes.len()
+        )));
+    }
+    let inputs = inputs_bytes
+        .chunks(32)
+        .map(Fr::deserialize_compressed)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let pvk = prepare_verifying_key(&vk);
+    let valid = Groth16::<Bn254>::verify_proof(&pvk, &proof, &inputs).map_err(ProverError::Verification)?;
+    Ok((valid, inputs))
+}
+
+/// Verify the payloads written by `utils::save_proof`, `save_public_input` and
+/// `save_verifying_key` (after their version byte); the proof keeps its `utils::frame_proof`
+/// tags, as `utils::read_versioned_proof` returns it, and may be in either encoding.
+/// Malformed bytes are an error rather than `false`, so callers can tell a bad file from a wrong proof.
+pub fn prepare_and_verify(proof_bytes: &[u8], input_bytes: &[u8], vk_bytes: &[u8]) -> Result<bool, ProverError> {
+    let (proof, _) = utils::decode_proof(utils::unframe_proof(proof_bytes)?)?;
+    let input = Fr::deserialize_uncompressed(input_bytes)?;
+    let vk = VerifyingKey::<Bn254>::deserialize_uncompressed(vk_bytes)?;
+    verify_proof(&proof, input, &vk)
+}
 
+/// Build the 164-byte calldata read by the PVM contract's `call()`:
+/// 4-byte selector, compressed proof, big-endian public input
+pub fn build_pvm_calldata(proof: &Proof<Bn254>, input: &Fr) -> [u8; 164] {
+    utils::build_calldata(proof, input, utils::CalldataMode::Pvm)
+        .expect("serializing into a Vec cannot fail")
+        .try_into()
+        .expect("PVM calldata is always 164 bytes")
+}
+
+/// Off-chain mirror of the PVM contract's `call()`: copies the calldata into a
+/// zero-padded 164-byte buffer, decodes it the same way and returns the same bool
+pub fn verify_pvm_calldata(calldata: &[u8], vk: &VerifyingKey<Bn254>) -> bool {
+    let mut buf = [0u8; 164];
+    let n = calldata.len().min(buf.len());
+    buf[..n].copy_from_slice(&calldata[..n]);
+
+    let proof_bytes = &buf[4..132];
+    let mut input_le = [0u8; 32];
+    input_le.copy_from_slice(&buf[132..164]);
+    input_le.reverse();
+
+    let Ok(proof) = Proof::<Bn254>::deserialize_compressed(proof_bytes) else {
+        return false;
+    };
+    let Ok(public) = Fr::deserialize_compressed(&input_le[..]) else {
+        return false;
+    };
+    let pvk = prepare_verifying_key(vk);
+    Groth16::<Bn254>::verify_proof(&pvk, &proof, &[public]).unwrap_or(false)
+}
+
+/// Bytes per row of the array written by `export_verifying_key_to_rs`
+pub const DEFAULT_VK_BYTES_PER_ROW: usize = 16;
+
+/// Public inputs the PVM verifier contract decodes from its 164-byte calldata
+pub const CONTRACT_PUBLIC_INPUTS: usize = 1;
+
+/// Export verifying key to `<out_dir>/keys/verifying_key_bytes.rs` for on-chain embedding
+pub fn export_verifying_key_to_rs(vk: &VerifyingKey<Bn254>, out_dir: &Path) -> Result<PathBuf, ProverError> {
+    export_verifying_key_to_rs_with_width(vk, out_dir, DEFAULT_VK_BYTES_PER_ROW)
+}
+
+/// As `export_verifying_key_to_rs`, with `bytes_per_row` bytes on each line of the array
+pub fn export_verifying_key_to_rs_with_width(
+    vk: &VerifyingKey<Bn254>,
+    out_dir: &Path,
+    bytes_per_row: usize,
+) -> Result<PathBuf, ProverError> {
+    let source = verifying_key_rs_source_with_width(vk, bytes_per_row)?;
+
+    let keys_dir = out_dir.join(utils::KEYS_DIR);
+    fs::create_dir_all(&keys_dir)?;
+
+    let out_path = keys_dir.join("verifying_key_bytes.rs");
+    let mut out = File::create(&out_path)?;
+    out.write_all(source.as_bytes())?;
+
+    Ok(out_path)
+}
+
+/// Rust source declaring `VERIFYING_KEY_BYTES` as the uncompressed verifying key,
+/// and `VERIFYING_KEY_LEN` as its length. The contract `include!`s this file, so a key
+/// for other than `CONTRACT_PUBLIC_INPUTS` public inputs is refused with `InvalidConfig`
+/// rather than embedded in a contract that could never verify a proof.
+pub fn verifying_key_rs_source(vk: &VerifyingKey<Bn254>) -> Result<String, ProverError> {
+    verifying_key_rs_source_with_width(vk, DEFAULT_VK_BYTES_PER_ROW)
+}
+
+/// As `verifying_key_rs_source`, with `bytes_per_row` bytes on each line of the array
+pub fn verifying_key_rs_source_with_width(vk: &VerifyingKey<Bn254>, bytes_per_row: usize) -> Result<String, ProverError> {
+    if bytes_per_row == 0 {
+        return Err(ProverError::InvalidConfig("bytes per row must be non-zero".to_string()));
+    }
+    if vk.gamma_abc_g1.len() != CONTRACT_PUBLIC_INPUTS + 1 {
+        return Err(ProverError::InvalidConfig(format!(
+            "the verifier contract takes {} public input, but the verifying key has {} gamma_abc_g1 points ({} inputs)",
+            CONTRACT_PUBLIC_INPUTS,
+            vk.gamma_abc_g1.len(),
+            vk.gamma_abc_g1.len().saturating_sub(1)
+        )));
+    }
+
+    let vk_bytes = embedded_verifying_key_bytes(vk)?;
+
+    let mut out = String::from("// Auto-generated verifying key byte array\n");
+    out.push_str(&format!("pub const VERIFYING_KEY_LEN: usize = {};\n", vk_bytes.len()));
+    out.push_str("pub const VERIFYING_KEY_BYTES: &[u8] = &[\n");
+    out.push_str(&hex_byte_rows(&vk_bytes, bytes_per_row));
+    out.push_str("];\n\n");
+
+    Ok(out)
+}
+
+/// Export verifying key to `<out_dir>/keys/verifying_key_bytes.ts`, the same bytes as the Rust array
+pub fn export_verifying_key_to_ts(vk: &VerifyingKey<Bn254>, out_dir: &Path) -> Result<PathBuf, ProverError> {
+    let source = verifying_key_ts_source(vk)?;
+
+    let keys_dir = out_dir.join(utils::KEYS_DIR);
+    fs::create_dir_all(&keys_dir)?;
+
+    let out_path = keys_dir.join("verifying_key_bytes.ts");
+    fs::write(&out_path, source)?;
+    Ok(out_path)
+}
+
+/// TypeScript source exporting `VERIFYING_KEY_BYTES` as a `Uint8Array` of the uncompressed verifying key
+pub fn verifying_key_ts_source(vk: &VerifyingKey<Bn254>) -> Result<String, ProverError> {
+    let vk_bytes = embedded_verifying_key_bytes(vk)?;
+
+    let mut out = String::from("// Auto-generated verifying key byte array\n");
+    out.push_str(&format!("export const VERIFYING_KEY_LEN = {};\n", vk_bytes.len()));
+    out.push_str("export const VERIFYING_KEY_BYTES = new Uint8Array([\n");
+    out.push_str(&hex_byte_rows(&vk_bytes, DEFAULT_VK_BYTES_PER_ROW));
+    out.push_str("]);\n");
+
+    Ok(out)
+}
+
+// The bytes every exporter embeds: the uncompressed key the contract deserializes
+fn embedded_verifying_key_bytes(vk: &VerifyingKey<Bn254>) -> Result<Vec<u8>, ProverError> {
     let mut vk_bytes = Vec::new();
     vk.serialize_uncompressed(&mut vk_bytes)?;
+    Ok(vk_bytes)
+}
 
-    let mut out = File::create("../keys/verifying_key_bytes.rs")?;
-    writeln!(out, "// Auto-generated verifying key byte array\npub const VERIFYING_KEY_BYTES: &[u8] = &[")?;
-    for chunk in vk_bytes.chunks(16) {
-        write!(out, "    ")?;
+// Indented `0x..,` rows, valid inside both a Rust slice and a TypeScript array literal
+fn hex_byte_rows(bytes: &[u8], bytes_per_row: usize) -> String {
+    let mut out = String::new();
+    for chunk in bytes.chunks(bytes_per_row) {
+        out.push_str("    ");
         for byte in chunk {
-            write!(out, "0x{:02x}, ", byte)?;
+            out.push_str(&format!("0x{:02x}, ", byte));
         }
-        writeln!(out)?;
+        out.push('\n');
     }
-    writeln!(out, "];\n")?;
+    out
+}
 
-    Ok(())
+/// Extract the byte array from a `verifying_key_bytes.rs`-style source file.
+/// Accepts both the hex rows written by `export_verifying_key_to_rs` and the
+/// decimal `&[1, 2, ...]` form older versions of `utils::export_verifying_key_to_rs` wrote.
+pub fn parse_verifying_key_rs_source(src: &str) -> Result<Vec<u8>, ProverError> {
+    let body = src
+        .split_once("VERIFYING_KEY_BYTES")
+        .and_then(|(_, rest)| rest.split_once('='))
+        .and_then(|(_, rest)| rest.split_once('['))
+        .and_then(|(_, rest)| rest.split_once(']'))
+        .map(|(body, _)| body)
+        .ok_or_else(|| ProverError::InvalidKeyFile("no `VERIFYING_KEY_BYTES` array found".to_string()))?;
+
+    body.split(',')
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+        .map(|token| {
+            let parsed = match token.strip_prefix("0x") {
+                Some(hex) => u8::from_str_radix(hex, 16),
+                None => token.parse::<u8>(),
+            };
+            parsed.map_err(|e| ProverError::InvalidKeyFile(format!("invalid byte `{}`: {}", token, e)))
+        })
+        .collect()
+}
+
+/// SHA-256 of the uncompressed verifying key, used to identify which key a contract embeds
+pub fn vk_fingerprint(vk: &VerifyingKey<Bn254>) -> [u8; 32] {
+    let mut bytes = Vec::new();
+    vk.serialize_uncompressed(&mut bytes)
+        .expect("serializing into a Vec cannot fail");
+    Sha256::digest(&bytes).into()
+}
+
+/// Whether two verifying keys agree on every curve point
+pub fn vk_equal(a: &VerifyingKey<Bn254>, b: &VerifyingKey<Bn254>) -> bool {
+    vk_diff(a, b).is_empty()
+}
+
+/// Names of the verifying-key components that differ between `a` and `b`
+pub fn vk_diff(a: &VerifyingKey<Bn254>, b: &VerifyingKey<Bn254>) -> Vec<String> {
+    let mut diff = Vec::new();
+    if a.alpha_g1 != b.alpha_g1 {
+        diff.push("alpha_g1".to_string());
+    }
+    if a.beta_g2 != b.beta_g2 {
+        diff.push("beta_g2".to_string());
+    }
+    if a.gamma_g2 != b.gamma_g2 {
+        diff.push("gamma_g2".to_string());
+    }
+    if a.delta_g2 != b.delta_g2 {
+        diff.push("delta_g2".to_string());
+    }
+    if a.gamma_abc_g1.len() != b.gamma_abc_g1.len() {
+        diff.push(format!("gamma_abc_g1 length ({} vs {})", a.gamma_abc_g1.len(), b.gamma_abc_g1.len()));
+    } else {
+        for (i, (x, y)) in a.gamma_abc_g1.iter().zip(&b.gamma_abc_g1).enumerate() {
+            if x != y {
+                diff.push(format!("gamma_abc_g1[{}]", i));
+            }
+        }
+    }
+    diff
 }
 
 /// Load a verifying key from a binary file
-pub fn load_verifying_key_from_file(path: &str) -> Result<VerifyingKey<Bn254>, Box<dyn std::error::Error>> {
-    let file = File::open(path)?;
-    let reader = BufReader::new(file);
-    let vk = VerifyingKey::<Bn254>::deserialize_uncompressed(reader)?;
-    Ok(vk)
+pub fn load_verifying_key_from_file(path: &str) -> Result<VerifyingKey<Bn254>, ProverError> {
+    utils::load_verifying_key(Path::new(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn valid_bytes() -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+        let (proof, c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let (mut vk, mut p, mut input) = (Vec::new(), Vec::new(), Vec::new());
+        pk.vk.serialize_uncompressed(&mut vk).unwrap();
+        proof.serialize_compressed(&mut p).unwrap();
+        c.serialize_compressed(&mut input).unwrap();
+        (vk, p, input)
+    }
+
+    #[test]
+    fn test_rerandomized_proof_differs_but_verifies() {
+        let (proof, c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let fresh = rerandomize_proof(&proof, &pk.vk);
+
+        let (mut original_bytes, mut fresh_bytes) = (Vec::new(), Vec::new());
+        proof.serialize_compressed(&mut original_bytes).unwrap();
+        fresh.serialize_compressed(&mut fresh_bytes).unwrap();
+        assert_ne!(original_bytes, fresh_bytes);
+
+        assert!(verify_proof(&fresh, c, &pk.vk).expect("verification failed"));
+        assert!(!verify_proof(&fresh, c + Fr::from(1u64), &pk.vk).expect("verification failed"));
+    }
+
+    #[test]
+    fn test_find_correct_public_input() {
+        let (proof, c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let candidates = vec![
+            vec![Fr::from(7u64)],
+            vec![],
+            vec![c, c],
+            vec![c],
+            vec![Fr::from(13u64)],
+        ];
+        assert_eq!(find_correct_public_input(&proof, &pk.vk, &candidates), Some(3));
+        assert_eq!(find_correct_public_input(&proof, &pk.vk, &candidates[..3]), None);
+    }
+
+    #[test]
+    fn test_verify_against_any_finds_the_matching_key() {
+        let (proof, c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let (_proof, _c, first) = generate_proof(3, 4).expect("proof generation failed");
+        let (_proof, _n, third) = prove_factorization(5, 7).expect("proof generation failed");
+        let vks = [first.vk, pk.vk, third.vk];
+
+        assert_eq!(verify_against_any(&proof, &[c], &vks), Some(1));
+        assert_eq!(verify_against_any(&proof, &[c + Fr::from(1u64)], &vks), None);
+        assert_eq!(verify_against_any(&proof, &[c], &[vks[0].clone(), vks[2].clone()]), None);
+    }
+
+    #[test]
+    fn test_verify_proof_bytes_accepts_valid_bytes() {
+        let (vk, proof, input) = valid_bytes();
+        assert!(verify_proof_bytes(&vk, &proof, &input));
+    }
+
+    #[test]
+    fn test_decode_and_verify_returns_the_serialized_inputs() {
+        use crate::circuit::{PublicInputsCircuit, RootRelation};
+
+        let (vk, proof, input) = valid_bytes();
+        assert_eq!(decode_and_verify(&vk, &proof, &input).unwrap(), (true, vec![Fr::from(12u64)]));
+
+        // 6 - 3x + 0x^2 = 0 has the root x = 2
+        let coeffs = [Fr::from(6u64), -Fr::from(3u64), Fr::from(0u64)];
+        let mut rng = thread_rng();
+        let (pk, vk) =
+            Groth16::<Bn254>::circuit_specific_setup(PublicInputsCircuit::setup_shape(3, RootRelation { x: None }), &mut rng)
+                .unwrap();
+        let instance = PublicInputsCircuit::instance(&coeffs, RootRelation { x: Some(Fr::from(2u64)) });
+        let proof = Groth16::<Bn254>::prove(&pk, instance, &mut rng).unwrap();
+
+        let (mut vk_bytes, mut proof_bytes, mut inputs_bytes) = (Vec::new(), Vec::new(), Vec::new());
+        vk.serialize_uncompressed(&mut vk_bytes).unwrap();
+        proof.serialize_compressed(&mut proof_bytes).unwrap();
+        for c in &coeffs {
+            c.serialize_compressed(&mut inputs_bytes).unwrap();
+        }
+        assert_eq!(decode_and_verify(&vk_bytes, &proof_bytes, &inputs_bytes).unwrap(), (true, coeffs.to_vec()));
+
+        // A wrong input still decodes, and is reported back alongside `false`
+        Fr::from(7u64).serialize_compressed(&mut &mut inputs_bytes[..32]).unwrap();
+        let (valid, decoded) = decode_and_verify(&vk_bytes, &proof_bytes, &inputs_bytes).unwrap();
+        assert!(!valid);
+        assert_eq!(decoded[0], Fr::from(7u64));
+
+        assert!(decode_and_verify(&vk_bytes, &proof_bytes, &inputs_bytes[..64]).is_err());
+        assert!(decode_and_verify(&vk_bytes, &proof_bytes, &inputs_bytes[..40]).is_err());
+    }
+
+    // Knowledge of a square root of 9, with nothing public
+    #[derive(Clone)]
+    struct NoInputsCircuit {
+        root: Option<Fr>,
+    }
+
+    impl ConstraintSynthesizer<Fr> for NoInputsCircuit {
+        fn generate_constraints(self, cs: ark_relations::r1cs::ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+            use ark_r1cs_std::{alloc::AllocVar, eq::EqGadget, fields::FieldVar, fields::fp::FpVar};
+
+            let root = FpVar::new_witness(cs, || self.root.ok_or(SynthesisError::AssignmentMissing))?;
+            (&root * &root).enforce_equal(&FpVar::constant(Fr::from(9u64)))
+        }
+    }
+
+    #[test]
+    fn test_zero_public_input_proof_verifies_against_an_empty_slice() {
+        let (pk, vk) = setup_circuit_specific(NoInputsCircuit { root: None }).unwrap();
+        assert_eq!(vk.gamma_abc_g1.len(), 1);
+        let proof = Groth16::<Bn254>::prove(&pk, NoInputsCircuit { root: Some(-Fr::from(3u64)) }, &mut thread_rng()).unwrap();
+
+        assert!(verify_proof_with_inputs(&proof, &[], &vk).unwrap());
+        assert!(matches!(verify_proof_with_inputs(&proof, &[Fr::from(9u64)], &vk), Err(ProverError::Verification(_))));
+
+        let (mut vk_bytes, mut proof_bytes) = (Vec::new(), Vec::new());
+        vk.serialize_uncompressed(&mut vk_bytes).unwrap();
+        proof.serialize_compressed(&mut proof_bytes).unwrap();
+        assert_eq!(decode_and_verify(&vk_bytes, &proof_bytes, &[]).unwrap(), (true, Vec::new()));
+
+        let calldata = utils::build_calldata_with_inputs::<Fr>(&proof, &[], utils::CalldataMode::Pvm).unwrap();
+        assert!(calldata::verify_calldata(&calldata, &vk).unwrap());
+    }
+
+    #[test]
+    fn test_verify_proof_bytes_near_real_lengths() {
+        let (vk, proof, input) = valid_bytes();
+        let variants = |bytes: &Vec<u8>| -> Vec<Vec<u8>> {
+            let mut extended = bytes.clone();
+            extended.push(0xff);
+            let mut flipped = bytes.clone();
+            let last = flipped.len() - 1;
+            flipped[last] ^= 0x01;
+            vec![bytes[..bytes.len() - 1].to_vec(), extended, flipped, Vec::new()]
+        };
+
+        let [truncated, extended, flipped, empty] = variants(&vk).try_into().unwrap();
+        for bad in [truncated, flipped, empty] {
+            assert!(!verify_proof_bytes(&bad, &proof, &input));
+        }
+        // A trailing byte after a complete key is not read
+        assert!(verify_proof_bytes(&extended, &proof, &input));
+        for bad in variants(&proof) {
+            assert!(!verify_proof_bytes(&vk, &bad, &input) || bad.starts_with(&proof));
+        }
+        for bad in variants(&input) {
+            assert!(!verify_proof_bytes(&vk, &proof, &bad) || bad.starts_with(&input));
+        }
+    }
+
+    #[test]
+    fn test_verify_proof_separates_wrong_proof_from_corrupt_key() {
+        let (proof, c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let (other, _c, _pk) = generate_proof(5, 6).expect("proof generation failed");
+        assert!(!verify_proof(&other, c, &pk.vk).expect("a wrong proof is not an error"));
+
+        let mut off_curve = proof.clone();
+        off_curve.a = ark_bn254::G1Affine::new_unchecked(ark_bn254::Fq::from(1u64), ark_bn254::Fq::from(1u64));
+        assert!(!verify_proof(&off_curve, c, &pk.vk).expect("an invalid point is not an error"));
+
+        let mut corrupt_vk = pk.vk.clone();
+        corrupt_vk.gamma_abc_g1.pop();
+        assert!(matches!(verify_proof(&proof, c, &corrupt_vk), Err(ProverError::Verification(_))));
+    }
+
+    #[test]
+    fn test_verify_proof_u64_takes_the_plain_product() {
+        let (proof, _c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        assert!(verify_proof_u64(&proof, 12, &pk.vk).expect("verification failed"));
+        assert!(!verify_proof_u64(&proof, 13, &pk.vk).expect("verification failed"));
+    }
+
+    #[test]
+    fn test_verify_proof_pinned_checks_the_fingerprint_first() {
+        let (proof, c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let (_proof, _c, other) = generate_proof(3, 4).expect("proof generation failed");
+
+        let pinned = vk_fingerprint(&pk.vk);
+        assert!(verify_proof_pinned(&proof, c, &pk.vk, pinned).expect("pinned key should verify"));
+
+        match verify_proof_pinned(&proof, c, &other.vk, pinned) {
+            Err(ProverError::VkMismatch { expected, found }) => {
+                assert_eq!((expected, found), (pinned, vk_fingerprint(&other.vk)));
+            }
+            other => panic!("expected VkMismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_vk_equal_to_itself_but_not_to_fresh_setup() {
+        let (_proof, _c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let (_proof, _c, fresh) = generate_proof(3, 4).expect("proof generation failed");
+
+        assert!(vk_equal(&pk.vk, &pk.vk.clone()));
+        assert!(!vk_equal(&pk.vk, &fresh.vk));
+        // Every setup samples fresh toxic waste, so all points differ
+        assert_eq!(
+            vk_diff(&pk.vk, &fresh.vk),
+            ["alpha_g1", "beta_g2", "gamma_g2", "delta_g2", "gamma_abc_g1[0]", "gamma_abc_g1[1]"]
+        );
+    }
+
+    #[test]
+    fn test_parse_fr_accepts_decimal_and_hex_below_the_modulus() {
+        assert_eq!(parse_fr("12345").unwrap(), Fr::from(12345u64));
+        assert_eq!(parse_fr(" 0x3039\n").unwrap(), Fr::from(12345u64));
+        let p_minus_one = "21888242871839275222246405745257275088548364400416034343698204186575808495616";
+        assert_eq!(parse_fr(p_minus_one).unwrap(), -Fr::from(1u64));
+
+        let p = "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+        for bad in [p, "", "0x", "-1", "12a", "0x1g", &format!("0x1{}", "0".repeat(64))] {
+            assert!(matches!(parse_fr(bad), Err(ProverError::InvalidFieldElement(_))), "accepted {:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_proving_a_setup_shape_names_the_missing_field() {
+        let (_proof, _c, pk) = generate_proof(3, 4).expect("proof generation failed");
+
+        let err = prove_circuit(&pk, MulCircuit::setup_shape()).expect_err("setup shape should not prove");
+        assert!(matches!(err, ProverError::MissingWitness { field: "a" }));
+        assert!(err.to_string().contains("`a`"));
+
+        let missing_c = MulCircuit { c: None, ..MulCircuit::instance(3, 4) };
+        assert!(matches!(prove_circuit(&pk, missing_c), Err(ProverError::MissingWitness { field: "c" })));
+        assert!(MulCircuit::instance(3, 4).is_instance());
+        assert!(prove_circuit(&pk, MulCircuit::instance(3, 4)).is_ok());
+    }
+
+    #[test]
+    fn test_proving_an_unsatisfied_circuit_is_an_error() {
+        let (_proof, _c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let wrong_c = MulCircuit { c: Some(Fr::from(13u64)), ..MulCircuit::instance(3, 4) };
+        assert!(matches!(prove_circuit(&pk, wrong_c), Err(ProverError::Unsatisfied { .. })));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_prove_many_parallel_proofs_all_verify() {
+        let (_proof, _c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let pairs: Vec<(u64, u64)> = (0..100).map(|i| (i, i + 7)).collect();
+
+        let proofs = prove_many_parallel(&pk, &pairs).expect("batch proving failed");
+        assert_eq!(proofs.len(), pairs.len());
+        for ((proof, c), &(a, b)) in proofs.iter().zip(&pairs) {
+            assert_eq!(*c, Fr::from(a * b));
+            assert!(verify_proof(proof, *c, &pk.vk).expect("verification failed"));
+        }
+    }
+
+    #[test]
+    fn test_proving_session_serves_several_statements() {
+        let (_proof, _c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let session = ProvingSession::new(pk);
+
+        for (a, b) in [(3, 4), (0, 9), (7, 11)] {
+            let (proof, c) = session.prove(a, b).expect("session proving failed");
+            assert_eq!(c, Fr::from(a * b));
+            assert!(verify_proof(&proof, c, session.verifying_key()).expect("verification failed"));
+        }
+    }
+
+    // Timing-sensitive, so not part of the default run:
+    // cargo test -p prover --release --features parallel -- --ignored --nocapture bench_prove_many
+    #[cfg(feature = "parallel")]
+    #[test]
+    #[ignore]
+    fn bench_prove_many_parallel_vs_sequential() {
+        use std::time::Instant;
+
+        let (_proof, _c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let pairs: Vec<(u64, u64)> = (0..200).map(|i| (i, i + 7)).collect();
+
+        let start = Instant::now();
+        for &(a, b) in &pairs {
+            prove_with_key(&pk, a, b, false).expect("proof generation failed");
+        }
+        let sequential = start.elapsed();
+
+        let start = Instant::now();
+        prove_many_parallel(&pk, &pairs).expect("batch proving failed");
+        let parallel = start.elapsed();
+
+        let threads = rayon::current_num_threads();
+        println!("{} proofs: sequential {:?}, parallel {:?} on {} threads", pairs.len(), sequential, parallel, threads);
+        if threads > 1 {
+            assert!(parallel < sequential);
+        }
+    }
+
+    #[test]
+    fn test_prove_hash_preimage_verifies() {
+        let secret = Fr::from(42u64);
+        let (proof, hash, pk) = prove_hash_preimage(secret).expect("proof generation failed");
+        assert_eq!(hash, poseidon::poseidon_sponge_hash(&[secret]));
+        assert!(verify_proof(&proof, hash, &pk.vk).expect("verification failed"));
+        assert!(!verify_proof(&proof, hash + Fr::from(1u64), &pk.vk).expect("verification failed"));
+    }
+
+    #[test]
+    fn test_prove_hash_output_returns_the_off_chain_hash() {
+        for secret in [Fr::from(0u64), Fr::from(42u64), -Fr::from(1u64)] {
+            let (proof, hash, pk) = prove_hash_output(secret).expect("proof generation failed");
+            assert_eq!(hash, poseidon::poseidon_sponge_hash(&[secret]));
+            assert!(verify_proof(&proof, hash, &pk.vk).expect("verification failed"));
+            assert!(!verify_proof(&proof, hash + Fr::from(1u64), &pk.vk).expect("verification failed"));
+        }
+    }
+
+    #[test]
+    fn test_snark_api_interchangeable_with_reduction_helpers() {
+        let mut rng = thread_rng();
+        let c = Fr::from(12u64);
+        let circuit = || MulCircuit { a: Some(Fr::from(3u64)), b: Some(Fr::from(4u64)), c: Some(c), nonzero: false };
+
+        let (snark_pk, _) = Groth16::<Bn254>::circuit_specific_setup(circuit(), &mut rng).unwrap();
+        let proof = Groth16::<Bn254>::create_random_proof_with_reduction(circuit(), &snark_pk, &mut rng).unwrap();
+        assert!(verify_proof(&proof, c, &snark_pk.vk).unwrap());
+
+        let reduction_pk = Groth16::<Bn254>::generate_random_parameters_with_reduction(circuit(), &mut rng).unwrap();
+        let proof = Groth16::<Bn254>::prove(&reduction_pk, circuit(), &mut rng).unwrap();
+        assert!(verify_proof(&proof, c, &reduction_pk.vk).unwrap());
+        assert!(Groth16::<Bn254>::verify(&reduction_pk.vk, &[c], &proof).unwrap());
+    }
+
+    #[test]
+    fn test_setup_ignores_assignments() {
+        let (shape_pk, _) = setup_circuit_specific(MulCircuit::setup_shape()).expect("setup failed");
+        let (instance_pk, _) = setup_circuit_specific(MulCircuit::instance(3, 4)).expect("setup failed");
+
+        let shape = |pk: &ProvingKey<Bn254>| {
+            (pk.a_query.len(), pk.b_g1_query.len(), pk.h_query.len(), pk.l_query.len(), pk.vk.gamma_abc_g1.len())
+        };
+        assert_eq!(shape(&shape_pk), shape(&instance_pk));
+
+        for pk in [&shape_pk, &instance_pk] {
+            let (proof, c) = prove_with_key(pk, 5, 6, false).expect("proof generation failed");
+            assert!(verify_proof(&proof, c, &pk.vk).expect("verification failed"));
+        }
+    }
+
+    #[test]
+    fn test_circuit_specific_setup_keys_verify_and_match_reduction_sizes() {
+        let (pk, vk) = setup_circuit_specific(MulCircuit::setup_shape()).expect("setup failed");
+        let (proof, c) = prove_with_key(&pk, 3, 4, false).expect("proof generation failed");
+        assert!(verify_proof(&proof, c, &vk).expect("verification failed"));
+        assert!(!verify_proof(&proof, c + Fr::from(1u64), &vk).expect("verification failed"));
+
+        let reduction_pk =
+            Groth16::<Bn254>::generate_random_parameters_with_reduction(MulCircuit::setup_shape(), &mut thread_rng())
+                .unwrap();
+        let sizes = |pk: &ProvingKey<Bn254>| (pk.compressed_size(), pk.vk.compressed_size());
+        println!("MulCircuit (pk, vk) bytes: circuit-specific {:?}, reduction {:?}", sizes(&pk), sizes(&reduction_pk));
+        assert_eq!(sizes(&pk), sizes(&reduction_pk));
+    }
+
+    #[test]
+    fn test_prepare_and_verify() {
+        let (vk, proof, input) = valid_bytes();
+        let proof = utils::frame_proof(&proof);
+        assert!(prepare_and_verify(&proof, &input, &vk).expect("valid bytes should decode"));
+
+        let (other_proof, _c, _pk) = generate_proof(5, 6).expect("proof generation failed");
+        let mut wrong = Vec::new();
+        other_proof.serialize_compressed(&mut wrong).unwrap();
+        let wrong = utils::frame_proof(&wrong);
+        assert!(!prepare_and_verify(&wrong, &input, &vk).expect("wrong proof should still decode"));
+
+        assert!(matches!(
+            prepare_and_verify(&proof[..10], &input, &vk),
+            Err(ProverError::Serialization(_))
+        ));
+        assert!(prepare_and_verify(&proof, &[0xff; 32], &vk).is_err());
+        assert!(prepare_and_verify(&proof, &input, &vk[..vk.len() / 2]).is_err());
+    }
+
+    #[test]
+    fn test_pvm_calldata_round_trip() {
+        let (proof, c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let calldata = build_pvm_calldata(&proof, &c);
+        assert!(verify_pvm_calldata(&calldata, &pk.vk));
+    }
+
+    #[test]
+    fn test_tampered_pvm_calldata_fails() {
+        let (proof, c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let calldata = build_pvm_calldata(&proof, &c);
+
+        let mut tampered_input = calldata;
+        tampered_input[163] ^= 0x01;
+        assert!(!verify_pvm_calldata(&tampered_input, &pk.vk));
+
+        let mut tampered_proof = calldata;
+        tampered_proof[10] ^= 0x01;
+        assert!(!verify_pvm_calldata(&tampered_proof, &pk.vk));
+
+        assert!(!verify_pvm_calldata(&calldata[..100], &pk.vk));
+    }
+
+    #[test]
+    fn test_verifying_key_rs_source_round_trip() {
+        let (_proof, _c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let src = verifying_key_rs_source(&pk.vk).expect("rendering failed");
+
+        let bytes = parse_verifying_key_rs_source(&src).expect("parsing failed");
+        let vk = VerifyingKey::<Bn254>::deserialize_uncompressed(&bytes[..]).expect("decoding failed");
+        assert_eq!(vk_fingerprint(&vk), vk_fingerprint(&pk.vk));
+
+        let decimal = format!("pub const VERIFYING_KEY_BYTES: &[u8] = &{:?};", bytes);
+        assert_eq!(parse_verifying_key_rs_source(&decimal).expect("parsing failed"), bytes);
+    }
+
+    #[test]
+    fn test_verifying_key_len_matches_emitted_bytes() {
+        let (_proof, _c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        for width in [DEFAULT_VK_BYTES_PER_ROW, 32, 1000] {
+            let src = verifying_key_rs_source_with_width(&pk.vk, width).expect("rendering failed");
+            let bytes = parse_verifying_key_rs_source(&src).expect("parsing failed");
+
+            let len: usize = src
+                .split_once("VERIFYING_KEY_LEN: usize = ")
+                .and_then(|(_, rest)| rest.split_once(';'))
+                .map(|(len, _)| len.parse().expect("length is not a number"))
+                .expect("no VERIFYING_KEY_LEN");
+            assert_eq!(len, bytes.len());
+            assert_eq!(len, pk.vk.uncompressed_size());
+
+            let rows = src.lines().filter(|line| line.starts_with("    0x")).count();
+            assert_eq!(rows, len.div_ceil(width));
+        }
+        assert!(verifying_key_rs_source_with_width(&pk.vk, 0).is_err());
+    }
+
+    #[test]
+    fn test_contract_key_export_refuses_other_input_counts() {
+        let (_proof, _c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let dir = std::env::temp_dir().join("poof_contract_key_input_count");
+
+        let mut two_inputs = pk.vk.clone();
+        two_inputs.gamma_abc_g1.push(pk.vk.gamma_abc_g1[1]);
+        let mut no_inputs = pk.vk.clone();
+        no_inputs.gamma_abc_g1.pop();
+
+        for vk in [two_inputs, no_inputs] {
+            let err = export_verifying_key_to_rs(&vk, &dir).expect_err("wrong input count must be refused");
+            assert!(matches!(&err, ProverError::InvalidConfig(msg) if msg.contains("gamma_abc_g1")), "{}", err);
+        }
+        assert!(!dir.join(utils::KEYS_DIR).join("verifying_key_bytes.rs").exists());
+    }
+
+    #[test]
+    fn test_verifying_key_ts_source_is_a_uint8array_literal() {
+        let (_proof, _c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let src = verifying_key_ts_source(&pk.vk).expect("rendering failed");
+
+        let body = src
+            .split_once("export const VERIFYING_KEY_BYTES = new Uint8Array([\n")
+            .and_then(|(_, rest)| rest.strip_suffix("]);\n"))
+            .expect("not a Uint8Array literal");
+        let bytes: Vec<u8> = body
+            .split(',')
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+            .map(|token| {
+                let hex = token.strip_prefix("0x").expect("byte is not 0x-prefixed");
+                u8::from_str_radix(hex, 16).expect("byte is not two hex digits")
+            })
+            .collect();
+
+        assert_eq!(bytes.len(), pk.vk.uncompressed_size());
+        assert!(src.contains(&format!("export const VERIFYING_KEY_LEN = {};", bytes.len())));
+        let rs_src = verifying_key_rs_source(&pk.vk).expect("rendering failed");
+        assert_eq!(bytes, parse_verifying_key_rs_source(&rs_src).expect("parsing failed"));
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(256))]
+
+        #[test]
+        fn fuzz_verify_proof_bytes_never_panics(
+            vk in proptest::collection::vec(any::<u8>(), 0..800),
+            proof in proptest::collection::vec(any::<u8>(), 0..200),
+            input in proptest::collection::vec(any::<u8>(), 0..64),
+        ) {
+            prop_assert!(!verify_proof_bytes(&vk, &proof, &input));
+        }
+    }
 }
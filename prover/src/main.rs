@@ -1,36 +1,34 @@
 // Minimal main.rs for CLI or test harness entrypoint.
 // Core logic is moved to lib.rs for reuse across CLI, tests, and smart contract targets.
 
-use ark_bn254::{Bn254, Fr};
+use ark_bn254::Bn254;
 use ark_groth16::Groth16;
+use ark_snark::SNARK;
 use rand::thread_rng;
 use prover::circuit::MulCircuit;
-use prover::utils::save_calldata;
+use prover::utils::{CalldataMode, save_calldata};
 use prover::utils::export_verifying_key_to_rs;
-use prover::generate_proof;
-use prover::verify_proof;
+use std::path::Path;
 
 // include!("../../keys/verifying_key_bytes.rs");
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("Generating zkSNARK proof and calldata...");
 
-    // Inputs for circuit: proving that a * b = c
-    let a = Fr::from(3u64);
-    let b = Fr::from(4u64);
-    let c = Fr::from(12u64);
-
-    // Use one instance for setup, one for proving
-    let setup_circuit = MulCircuit { a: Some(a), b: Some(b), c: Some(c) };
-    let prove_circuit = MulCircuit { a: Some(a), b: Some(b), c: Some(c) };
+    // Inputs for circuit: proving that 3 * 4 = c
+    let setup_circuit = MulCircuit::setup_shape();
+    let prove_circuit = MulCircuit::instance(3, 4);
+    let c = prove_circuit.c.expect("instance assigns c");
 
     let mut rng = thread_rng();
 
-    let params = Groth16::<Bn254>::generate_random_parameters_with_reduction(setup_circuit, &mut rng)?;
-    let proof = Groth16::<Bn254>::create_random_proof_with_reduction(prove_circuit, &params, &mut rng)?;
+    let (params, _) = Groth16::<Bn254>::circuit_specific_setup(setup_circuit, &mut rng)?;
+    let proof = Groth16::<Bn254>::prove(&params, prove_circuit, &mut rng)?;
 
-    save_calldata(&proof, &c, "../calldata.bin")?;
-    export_verifying_key_to_rs(&params.vk)?;
+    // Artefacts go to the workspace root, where the contract `include!`s the key from
+    let out_dir = Path::new("..");
+    save_calldata(&proof, &c, &out_dir.join("calldata.bin"), CalldataMode::Pvm)?;
+    export_verifying_key_to_rs(&params.vk, out_dir)?;
 
     println!("✅ Calldata written to ../calldata.bin");
     Ok(())
@@ -41,6 +39,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 mod tests {
     use super::*;
     use ark_bn254::Fr;
+    use prover::generate_proof;
+    use prover::verify_proof;
 
     #[test]
     fn test_valid_proof_verifies() {
@@ -62,7 +62,8 @@ mod tests {
     #[test]
     fn test_export_verifying_key_to_rs() {
         let (_proof, _c, pk) = generate_proof(3, 4).expect("proof generation failed");
-        export_verifying_key_to_rs(&pk.vk).expect("export failed");
-        assert!(std::path::Path::new("../keys/verifying_key_bytes.rs").exists());
+        let path = export_verifying_key_to_rs(&pk.vk, Path::new("..")).expect("export failed");
+        assert_eq!(path, Path::new("../keys/verifying_key_bytes.rs"));
+        assert!(path.exists());
     }
 }
@@ -0,0 +1,364 @@
+// Poseidon sponge hash over BN254 scalars.
+// Absorbs a variable-length message and squeezes one field element, with a
+// matching R1CS gadget so circuits can recompute the same digest.
+// Parameters: width 3 (rate 2, capacity 1), x^5 S-box, 8 full and 57 partial rounds,
+// round constants and MDS matrix derived with the Grain LFSR, once per process.
+// Other parameter sets go through `PoseidonConfigBuilder`, which validates them, and
+// hash with the `*_with_config` variants.
+// Byte strings hash through `poseidon_hash_bytes`, which packs them into field elements first.
+
+use ark_bn254::Fr;
+use ark_crypto_primitives::sponge::constraints::CryptographicSpongeVar;
+use ark_crypto_primitives::sponge::poseidon::constraints::PoseidonSpongeVar;
+use ark_crypto_primitives::sponge::poseidon::traits::find_poseidon_ark_and_mds;
+use ark_crypto_primitives::sponge::poseidon::{PoseidonConfig, PoseidonSponge};
+use ark_crypto_primitives::sponge::CryptographicSponge;
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_r1cs_std::fields::FieldVar;
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use std::sync::LazyLock;
+
+use crate::error::ProverError;
+
+const RATE: usize = 2;
+const CAPACITY: usize = 1;
+const ALPHA: u64 = 5;
+const FULL_ROUNDS: usize = 8;
+const PARTIAL_ROUNDS: usize = 57;
+
+/// Bytes `poseidon_hash_bytes` packs into each field element; 31 bytes are below the
+/// 254-bit modulus, so packing never wraps
+pub const BYTES_PER_ELEMENT: usize = 31;
+
+// Running the Grain LFSR costs far more than cloning its output
+static DEFAULT_CONFIG: LazyLock<PoseidonConfig<Fr>> = LazyLock::new(compute_default_config);
+
+/// Poseidon parameters used by `poseidon_sponge_hash` and its gadget, computed on first use
+pub fn default_poseidon_config() -> PoseidonConfig<Fr> {
+    DEFAULT_CONFIG.clone()
+}
+
+fn compute_default_config() -> PoseidonConfig<Fr> {
+    let (ark, mds) = find_poseidon_ark_and_mds::<Fr>(
+        Fr::MODULUS_BIT_SIZE as u64,
+        RATE,
+        FULL_ROUNDS as u64,
+        PARTIAL_ROUNDS as u64,
+        0,
+    );
+    PoseidonConfig::new(FULL_ROUNDS, PARTIAL_ROUNDS, ALPHA, mds, ark, RATE, CAPACITY)
+}
+
+/// Fluent construction of a `PoseidonConfig`; unset fields keep the defaults above
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoseidonConfigBuilder {
+    full_rounds: usize,
+    partial_rounds: usize,
+    alpha: u64,
+    rate: usize,
+    capacity: usize,
+    skip_matrices: u64,
+}
+
+impl Default for PoseidonConfigBuilder {
+    fn default() -> Self {
+        Self {
+            full_rounds: FULL_ROUNDS,
+            partial_rounds: PARTIAL_ROUNDS,
+            alpha: ALPHA,
+            rate: RATE,
+            capacity: CAPACITY,
+            skip_matrices: 0,
+        }
+    }
+}
+
+impl PoseidonConfigBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn full_rounds(mut self, full_rounds: usize) -> Self {
+        self.full_rounds = full_rounds;
+        self
+    }
+
+    pub fn partial_rounds(mut self, partial_rounds: usize) -> Self {
+        self.partial_rounds = partial_rounds;
+        self
+    }
+
+    /// Exponent of the S-box `x^alpha`
+    pub fn alpha(mut self, alpha: u64) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    pub fn rate(mut self, rate: usize) -> Self {
+        self.rate = rate;
+        self
+    }
+
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Number of candidate MDS matrices the Grain LFSR discards before picking one
+    pub fn skip_matrices(mut self, skip_matrices: u64) -> Self {
+        self.skip_matrices = skip_matrices;
+        self
+    }
+
+    /// Check the parameters, then derive the round constants and MDS matrix
+    pub fn build(self) -> Result<PoseidonConfig<Fr>, ProverError> {
+        if self.rate == 0 || self.capacity == 0 {
+            return Err(ProverError::InvalidConfig("rate and capacity must be non-zero".to_string()));
+        }
+        if self.full_rounds == 0 || !self.full_rounds.is_multiple_of(2) {
+            return Err(ProverError::InvalidConfig(format!(
+                "full rounds must be a non-zero even number, got {}",
+                self.full_rounds
+            )));
+        }
+        if self.alpha < 3 || gcd(self.alpha, modulus_minus_one_rem(self.alpha)) != 1 {
+            return Err(ProverError::InvalidConfig(format!(
+                "alpha = {} must be greater than 1 and coprime to p - 1 for x^alpha to be a permutation",
+                self.alpha
+            )));
+        }
+        // find_poseidon_ark_and_mds only produces a state of width rate + 1
+        if self.capacity != 1 {
+            return Err(ProverError::InvalidConfig(format!(
+                "only capacity 1 is supported, got {}",
+                self.capacity
+            )));
+        }
+
+        let (ark, mds) = find_poseidon_ark_and_mds::<Fr>(
+            Fr::MODULUS_BIT_SIZE as u64,
+            self.rate,
+            self.full_rounds as u64,
+            self.partial_rounds as u64,
+            self.skip_matrices,
+        );
+        Ok(PoseidonConfig::new(
+            self.full_rounds,
+            self.partial_rounds,
+            self.alpha,
+            mds,
+            ark,
+            self.rate,
+            self.capacity,
+        ))
+    }
+}
+
+// (p - 1) mod m, by long division over the modulus limbs
+fn modulus_minus_one_rem(m: u64) -> u64 {
+    let mut p_minus_one = Fr::MODULUS;
+    p_minus_one.sub_with_borrow(&1u64.into());
+    p_minus_one
+        .as_ref()
+        .iter()
+        .rev()
+        .fold(0u64, |rem, &limb| ((((rem as u128) << 64) | limb as u128) % m as u128) as u64)
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Hash a message of any length to one field element.
+/// The length is absorbed first, so `[x]` and `[x, 0]` hash differently.
+pub fn poseidon_sponge_hash(inputs: &[Fr]) -> Fr {
+    poseidon_sponge_hash_with_config(&DEFAULT_CONFIG, inputs)
+}
+
+/// `poseidon_sponge_hash` under other parameters, e.g. a rate-1 config from `PoseidonConfigBuilder`
+pub fn poseidon_sponge_hash_with_config(config: &PoseidonConfig<Fr>, inputs: &[Fr]) -> Fr {
+    let mut sponge = PoseidonSponge::new(config);
+    sponge.absorb(&Fr::from(inputs.len() as u64));
+    sponge.absorb(&inputs);
+    sponge.squeeze_field_elements(1)[0]
+}
+
+/// Hash a byte string to one field element.
+/// The bytes are split into 31-byte chunks (the last one may be shorter), each read as a
+/// little-endian integer, and `poseidon_sponge_hash` runs over the byte length followed by
+/// those chunks: `poseidon_sponge_hash([len, chunk_0, chunk_1, ...])`. The byte length keeps
+/// strings that differ only in trailing zero bytes apart.
+pub fn poseidon_hash_bytes(data: &[u8]) -> Fr {
+    let mut elements = Vec::with_capacity(1 + data.len().div_ceil(BYTES_PER_ELEMENT));
+    elements.push(Fr::from(data.len() as u64));
+    elements.extend(data.chunks(BYTES_PER_ELEMENT).map(Fr::from_le_bytes_mod_order));
+    poseidon_sponge_hash(&elements)
+}
+
+/// In-circuit counterpart of `poseidon_sponge_hash`; the message length is fixed by the circuit shape
+pub fn poseidon_sponge_hash_gadget(
+    cs: ConstraintSystemRef<Fr>,
+    inputs: &[FpVar<Fr>],
+) -> Result<FpVar<Fr>, SynthesisError> {
+    poseidon_sponge_hash_gadget_with_config(cs, &DEFAULT_CONFIG, inputs)
+}
+
+/// In-circuit counterpart of `poseidon_sponge_hash_with_config`
+pub fn poseidon_sponge_hash_gadget_with_config(
+    cs: ConstraintSystemRef<Fr>,
+    config: &PoseidonConfig<Fr>,
+    inputs: &[FpVar<Fr>],
+) -> Result<FpVar<Fr>, SynthesisError> {
+    let mut sponge = PoseidonSpongeVar::new(cs, config);
+    sponge.absorb(&FpVar::constant(Fr::from(inputs.len() as u64)))?;
+    sponge.absorb(&inputs)?;
+    let mut out = sponge.squeeze_field_elements(1)?;
+    Ok(out.remove(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_r1cs_std::alloc::AllocVar;
+    use ark_r1cs_std::R1CSVar;
+    use ark_relations::r1cs::ConstraintSystem;
+    use sha2::{Digest, Sha256};
+    use std::time::Instant;
+
+    fn message(len: u64) -> Vec<Fr> {
+        (1..=len).map(Fr::from).collect()
+    }
+
+    #[test]
+    fn test_sponge_hash_is_deterministic() {
+        assert_eq!(poseidon_sponge_hash(&message(5)), poseidon_sponge_hash(&message(5)));
+    }
+
+    #[test]
+    fn test_different_lengths_hash_differently() {
+        let digests: Vec<Fr> = (0..6).map(|len| poseidon_sponge_hash(&message(len))).collect();
+        for i in 0..digests.len() {
+            for j in i + 1..digests.len() {
+                assert_ne!(digests[i], digests[j], "lengths {} and {} collide", i, j);
+            }
+        }
+
+        let x = Fr::from(7u64);
+        assert_ne!(poseidon_sponge_hash(&[x]), poseidon_sponge_hash(&[x, Fr::from(0u64)]));
+    }
+
+    #[test]
+    fn test_hash_bytes_is_deterministic_and_follows_the_packing() {
+        let data: Vec<u8> = (0..70).collect();
+        assert_eq!(poseidon_hash_bytes(&data), poseidon_hash_bytes(&data));
+
+        let chunks = [&data[..31], &data[31..62], &data[62..]].map(Fr::from_le_bytes_mod_order);
+        assert_eq!(poseidon_hash_bytes(&data), poseidon_sponge_hash(&[&[Fr::from(70u64)], &chunks[..]].concat()));
+    }
+
+    #[test]
+    fn test_hash_bytes_of_empty_and_one_byte_inputs_are_distinct_and_stable() {
+        let (empty, zero_byte) = (poseidon_hash_bytes(&[]), poseidon_hash_bytes(&[0]));
+        assert_ne!(empty, zero_byte);
+        assert_ne!(zero_byte, poseidon_hash_bytes(&[0, 0]));
+
+        // Pinned so a change to the packing or the parameters shows up here
+        let pinned = |digits| crate::parse_fr(digits).unwrap();
+        assert_eq!(empty, pinned("12117641782415882442765837307354927853150769952451275241092660529334768292512"));
+        assert_eq!(zero_byte, pinned("5387011825169656778446793288680041365579774886481689944759650640349498378200"));
+    }
+
+    #[test]
+    fn test_builder_defaults_match_hand_built_config() {
+        let built = PoseidonConfigBuilder::new().build().expect("default parameters are valid");
+        let expected = default_poseidon_config();
+
+        assert_eq!(built.full_rounds, expected.full_rounds);
+        assert_eq!(built.partial_rounds, expected.partial_rounds);
+        assert_eq!(built.alpha, expected.alpha);
+        assert_eq!((built.rate, built.capacity), (expected.rate, expected.capacity));
+        assert_eq!(built.ark, expected.ark);
+        assert_eq!(built.mds, expected.mds);
+    }
+
+    #[test]
+    fn test_default_config_is_computed_once() {
+        let digest = |config: &PoseidonConfig<Fr>| -> Vec<u8> {
+            let mut hasher = Sha256::new();
+            for x in config.ark.iter().chain(config.mds.iter()).flatten() {
+                hasher.update(x.into_bigint().to_bytes_le());
+            }
+            hasher.finalize().to_vec()
+        };
+        let first = default_poseidon_config();
+        assert_eq!(digest(&first), digest(&default_poseidon_config()));
+
+        let start = Instant::now();
+        let fresh = compute_default_config();
+        let uncached = start.elapsed();
+        let start = Instant::now();
+        let cached = default_poseidon_config();
+        assert!(start.elapsed() < uncached);
+        assert_eq!(digest(&fresh), digest(&cached));
+    }
+
+    #[test]
+    fn test_builder_rejects_alpha_sharing_a_factor_with_p_minus_one() {
+        // p - 1 is divisible by 3 for BN254's scalar field
+        let err = PoseidonConfigBuilder::new().alpha(3).build();
+        assert!(matches!(err, Err(ProverError::InvalidConfig(_))));
+        assert!(PoseidonConfigBuilder::new().full_rounds(7).build().is_err());
+    }
+
+    #[test]
+    fn test_builder_accepts_alpha_5_and_rejects_even_alpha() {
+        assert!(PoseidonConfigBuilder::new().alpha(5).build().is_ok());
+        for alpha in [4, 6, 8] {
+            let err = PoseidonConfigBuilder::new().alpha(alpha).build();
+            assert!(matches!(err, Err(ProverError::InvalidConfig(_))), "alpha {} accepted", alpha);
+        }
+    }
+
+    #[test]
+    fn test_gadget_matches_native_hash() {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let inputs = message(3);
+        let vars: Vec<FpVar<Fr>> = inputs
+            .iter()
+            .map(|x| FpVar::new_witness(cs.clone(), || Ok(*x)).unwrap())
+            .collect();
+
+        let digest = poseidon_sponge_hash_gadget(cs.clone(), &vars).unwrap();
+        assert_eq!(digest.value().unwrap(), poseidon_sponge_hash(&inputs));
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_gadget_matches_native_hash_at_each_rate() {
+        // Lengths on both sides of every block boundary, so a padding mismatch shows up
+        for rate in [1, 2, 3] {
+            let config = PoseidonConfigBuilder::new().rate(rate).build().expect("valid parameters");
+            for len in 0..=2 * rate as u64 + 1 {
+                let cs = ConstraintSystem::<Fr>::new_ref();
+                let inputs = message(len);
+                let vars: Vec<FpVar<Fr>> = inputs
+                    .iter()
+                    .map(|x| FpVar::new_witness(cs.clone(), || Ok(*x)).unwrap())
+                    .collect();
+
+                let digest = poseidon_sponge_hash_gadget_with_config(cs.clone(), &config, &vars).unwrap();
+                let native = poseidon_sponge_hash_with_config(&config, &inputs);
+                assert_eq!(digest.value().unwrap(), native, "rate {} length {}", rate, len);
+                assert!(cs.is_satisfied().unwrap());
+            }
+        }
+
+        let rate_one = PoseidonConfigBuilder::new().rate(1).build().expect("valid parameters");
+        let secret = [Fr::from(42u64)];
+        assert_ne!(poseidon_sponge_hash_with_config(&rate_one, &secret), poseidon_sponge_hash(&secret));
+    }
+}
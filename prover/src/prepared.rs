@@ -0,0 +1,222 @@
+// Repeated proving of `MulCircuit` with one proving key.
+// `Groth16::prove` synthesizes the circuit in a mode that also builds its R1CS
+// matrices, then throws them away after every proof. The matrices only depend
+// on the circuit's shape, so `PreparedMulProver` builds them once and each proof
+// only runs witness generation (no matrix construction or LC inlining).
+//
+// What can and cannot be cached:
+// - cached: the constraint matrices A, B, C and the instance/constraint counts
+// - already precomputed by setup: the proving key's query vectors
+// - per proof: the witness assignment (depends on a and b), the QAP witness map
+//   (FFTs over the constraint domain), the MSMs, and fresh blinding factors r, s
+// The per-proof work dominates. For `MulCircuit` synthesis drops from ~3µs to
+// ~1µs, against ~1.6ms of MSMs per proof, so the end-to-end gain is within noise.
+// The saving grows with the constraint count, as matrix construction and LC
+// inlining are linear in it, while arkworks offers no way to reuse the MSM bases.
+// `prove_with_assignment` skips synthesis altogether for callers that already hold
+// the variable assignment, e.g. computed by their own witness generator.
+
+use ark_bn254::{Bn254, Fr};
+use ark_groth16::{Groth16, Proof, ProvingKey};
+use ark_relations::r1cs::{
+    ConstraintMatrices, ConstraintSynthesizer, ConstraintSystem, OptimizationGoal, SynthesisError, SynthesisMode,
+};
+use ark_std::UniformRand;
+use rand::thread_rng;
+
+use crate::circuit::MulCircuit;
+use crate::error::ProverError;
+
+pub struct PreparedMulProver {
+    pk: ProvingKey<Bn254>,
+    nonzero: bool,
+    matrices: ConstraintMatrices<Fr>,
+}
+
+impl PreparedMulProver {
+    /// Synthesize the circuit's matrices once for proofs under `pk`.
+    /// Fails if `pk` was not set up for the circuit `nonzero` selects.
+    pub fn new(pk: ProvingKey<Bn254>, nonzero: bool) -> Result<Self, ProverError> {
+        let cs = ConstraintSystem::new_ref();
+        cs.set_optimization_goal(OptimizationGoal::Constraints);
+        cs.set_mode(SynthesisMode::Setup);
+        MulCircuit { nonzero, ..MulCircuit::setup_shape() }.generate_constraints(cs.clone())?;
+        cs.finalize();
+        let matrices = cs.to_matrices().ok_or(SynthesisError::MissingCS)?;
+
+        // A key for the other circuit would still prove, but nothing it proves would verify
+        let variables = matrices.num_instance_variables + matrices.num_witness_variables;
+        if pk.vk.gamma_abc_g1.len() != matrices.num_instance_variables || pk.a_query.len() != variables {
+            return Err(ProverError::InvalidConfig(format!(
+                "proving key is for {} instance and {} total variables, the circuit has {} and {}",
+                pk.vk.gamma_abc_g1.len(),
+                pk.a_query.len(),
+                matrices.num_instance_variables,
+                variables
+            )));
+        }
+        Ok(Self { pk, nonzero, matrices })
+    }
+
+    pub fn proving_key(&self) -> &ProvingKey<Bn254> {
+        &self.pk
+    }
+
+    /// Prove a * b = c, returning the proof and the public output c
+    pub fn prove(&self, a: u64, b: u64) -> Result<(Proof<Bn254>, Fr), ProverError> {
+        let (instance_vars, witness_vars, c) = self.assignment(a, b)?;
+        let proof = self.prove_with_assignment(&instance_vars, &witness_vars)?;
+        Ok((proof, c))
+    }
+
+    /// Prove from a precomputed assignment, with no circuit synthesis at all.
+    /// `instance_vars` starts with the constant one, followed by the public inputs, and
+    /// `witness_vars` holds the private variables, both in the circuit's allocation order
+    /// (arkworks' `instance_assignment` and `witness_assignment`).
+    ///
+    /// Only the variable counts are checked. An assignment that does not satisfy the
+    /// constraints, or lists variables in another order, still yields a proof, but one
+    /// that fails verification; use `check_satisfied` on the circuit while developing.
+    pub fn prove_with_assignment(&self, instance_vars: &[Fr], witness_vars: &[Fr]) -> Result<Proof<Bn254>, ProverError> {
+        let m = &self.matrices;
+        if instance_vars.len() != m.num_instance_variables || witness_vars.len() != m.num_witness_variables {
+            return Err(ProverError::InvalidConfig(format!(
+                "expected {} instance (including the constant one) and {} witness variables, got {} and {}",
+                m.num_instance_variables,
+                m.num_witness_variables,
+                instance_vars.len(),
+                witness_vars.len()
+            )));
+        }
+
+        let mut rng = thread_rng();
+        let r = Fr::rand(&mut rng);
+        let s = Fr::rand(&mut rng);
+        let full_assignment = [instance_vars, witness_vars].concat();
+
+        Ok(Groth16::<Bn254>::create_proof_with_reduction_and_matrices(
+            &self.pk,
+            r,
+            s,
+            m,
+            m.num_instance_variables,
+            m.num_constraints,
+            &full_assignment,
+        )?)
+    }
+
+    // Witness generation only: no matrices are built for this instance
+    fn assignment(&self, a: u64, b: u64) -> Result<(Vec<Fr>, Vec<Fr>, Fr), SynthesisError> {
+        let instance = MulCircuit { nonzero: self.nonzero, ..MulCircuit::instance(a, b) };
+        let c = instance.c.expect("instance assigns c");
+
+        let cs = ConstraintSystem::new_ref();
+        cs.set_optimization_goal(OptimizationGoal::Constraints);
+        cs.set_mode(SynthesisMode::Prove { construct_matrices: false });
+        instance.generate_constraints(cs.clone())?;
+
+        let cs = cs.into_inner().ok_or(SynthesisError::MissingCS)?;
+        Ok((cs.instance_assignment, cs.witness_assignment, c))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_proof, prove_with_key, verify_proof};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_prepared_proofs_verify() {
+        let (_proof, _c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let prover = PreparedMulProver::new(pk, false).expect("preparation failed");
+
+        for (a, b) in [(3, 4), (7, 9), (0, 5)] {
+            let (proof, c) = prover.prove(a, b).expect("proof generation failed");
+            assert_eq!(c, Fr::from(a * b));
+            assert!(verify_proof(&proof, c, &prover.proving_key().vk).expect("verification failed"));
+            assert!(!verify_proof(&proof, c + Fr::from(1u64), &prover.proving_key().vk).expect("verification failed"));
+        }
+    }
+
+    #[test]
+    fn test_key_for_another_circuit_is_refused() {
+        let (_proof, _c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let err = PreparedMulProver::new(pk, true);
+        assert!(matches!(err, Err(ProverError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_proof_from_precomputed_assignment_verifies() {
+        let (proof, c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let prover = PreparedMulProver::new(pk, false).expect("preparation failed");
+        let vk = &prover.proving_key().vk;
+
+        // The assignment a synthesized MulCircuit instance produces: [1, c] and its private variables
+        let cs = ConstraintSystem::new_ref();
+        MulCircuit::instance(3, 4).generate_constraints(cs.clone()).expect("synthesis failed");
+        let cs = cs.into_inner().expect("constraint system still shared");
+        assert_eq!(cs.instance_assignment, [Fr::from(1u64), c]);
+
+        let from_assignment = prover
+            .prove_with_assignment(&cs.instance_assignment, &cs.witness_assignment)
+            .expect("proof generation failed");
+        assert!(verify_proof(&proof, c, vk).expect("verification failed"));
+        assert!(verify_proof(&from_assignment, c, vk).expect("verification failed"));
+
+        // A mismatched assignment is not caught, but its proof does not verify
+        let mut wrong_witness = cs.witness_assignment.clone();
+        wrong_witness[1] += Fr::from(1u64);
+        let forged = prover.prove_with_assignment(&cs.instance_assignment, &wrong_witness).expect("proving failed");
+        assert!(!verify_proof(&forged, c, vk).expect("verification failed"));
+
+        let err = prover.prove_with_assignment(&cs.instance_assignment[1..], &cs.witness_assignment);
+        assert!(matches!(err, Err(ProverError::InvalidConfig(_))));
+    }
+
+    // What `Groth16::prove` synthesizes per proof before the QAP reduction
+    fn naive_synthesis(a: u64, b: u64) {
+        let cs = ConstraintSystem::new_ref();
+        cs.set_optimization_goal(OptimizationGoal::Constraints);
+        cs.set_mode(SynthesisMode::Prove { construct_matrices: true });
+        MulCircuit::instance(a, b).generate_constraints(cs.clone()).expect("synthesis failed");
+        cs.finalize();
+        cs.to_matrices().expect("matrices missing");
+    }
+
+    // Timing-sensitive, so not part of the default run:
+    // cargo test -p prover --release -- --ignored --nocapture bench_prepared
+    // The two paths are interleaved and compared by their fastest run, so
+    // frequency scaling and scheduler noise hit both equally. Only the synthesis
+    // stage is asserted on: end to end, the MSMs shared by both paths dominate.
+    #[test]
+    #[ignore]
+    fn bench_prepared_vs_naive_proving() {
+        const ROUNDS: u64 = 300;
+        let (_proof, _c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let prover = PreparedMulProver::new(pk.clone(), false).expect("preparation failed");
+
+        let time = |f: &dyn Fn()| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        };
+        let [mut naive, mut prepared, mut naive_synth, mut prepared_synth] = [Duration::MAX; 4];
+        for i in 0..ROUNDS {
+            naive = naive.min(time(&|| {
+                prove_with_key(&pk, i, i + 1, false).expect("proof generation failed");
+            }));
+            prepared = prepared.min(time(&|| {
+                prover.prove(i, i + 1).expect("proof generation failed");
+            }));
+            naive_synth = naive_synth.min(time(&|| naive_synthesis(i, i + 1)));
+            prepared_synth = prepared_synth.min(time(&|| {
+                prover.assignment(i, i + 1).expect("synthesis failed");
+            }));
+        }
+
+        println!("fastest proof: naive {:?}, prepared {:?}", naive, prepared);
+        println!("fastest synthesis: naive {:?}, prepared {:?}", naive_synth, prepared_synth);
+        assert!(prepared_synth < naive_synth);
+    }
+}
@@ -0,0 +1,127 @@
+// Constraint matrices of a circuit as JSON, for cross-checking it in other proving stacks.
+// `export_r1cs_json` synthesizes in setup mode, so a `setup_shape` is enough;
+// `r1cs_export_with_assignment` proves an `instance` and adds its assignment. Schema:
+// - `num_variables`: 1 + `num_public_inputs` + `num_private_variables`
+// - `num_public_inputs` / `num_private_variables`: instance and witness variable counts
+// - `constraints`: one `{ "a", "b", "c" }` object per constraint <a, z> * <b, z> = <c, z>,
+//   each a sparse list of `[variable, "coefficient"]` pairs with decimal coefficients.
+// - `instance_assignment` / `witness_assignment`: decimal values of the instance variables
+//   (constant one first) and the witness variables; only present for an exported instance.
+// Variables are numbered as arkworks does: 0 is the constant one, then the public inputs
+// in allocation order, then the private variables.
+
+use ark_bn254::Fr;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem, SynthesisError, SynthesisMode};
+use serde::Serialize;
+use std::path::Path;
+
+use crate::error::ProverError;
+
+/// A sparse row: `(variable, coefficient)` pairs
+pub type SparseRow = Vec<(usize, String)>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct R1csConstraint {
+    pub a: SparseRow,
+    pub b: SparseRow,
+    pub c: SparseRow,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct R1csExport {
+    pub num_variables: usize,
+    pub num_public_inputs: usize,
+    pub num_private_variables: usize,
+    pub constraints: Vec<R1csConstraint>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance_assignment: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub witness_assignment: Option<Vec<String>>,
+}
+
+/// The constraint matrices of `circuit` in the schema above
+pub fn r1cs_export<C: ConstraintSynthesizer<Fr>>(circuit: C) -> Result<R1csExport, ProverError> {
+    export(circuit, SynthesisMode::Setup)
+}
+
+/// As `r1cs_export`, for an assigned `instance`, whose variable values are exported too
+pub fn r1cs_export_with_assignment<C: ConstraintSynthesizer<Fr>>(instance: C) -> Result<R1csExport, ProverError> {
+    export(instance, SynthesisMode::Prove { construct_matrices: true })
+}
+
+fn export<C: ConstraintSynthesizer<Fr>>(circuit: C, mode: SynthesisMode) -> Result<R1csExport, ProverError> {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    cs.set_mode(mode);
+    circuit.generate_constraints(cs.clone())?;
+    cs.finalize();
+
+    let matrices = cs.to_matrices().ok_or(SynthesisError::MissingCS)?;
+    let row = |row: &[(Fr, usize)]| -> SparseRow {
+        row.iter().map(|(coeff, var)| (*var, coeff.to_string())).collect()
+    };
+    let constraints = (0..matrices.num_constraints)
+        .map(|i| R1csConstraint { a: row(&matrices.a[i]), b: row(&matrices.b[i]), c: row(&matrices.c[i]) })
+        .collect();
+
+    let strings = |values: &[Fr]| values.iter().map(Fr::to_string).collect::<Vec<_>>();
+    let (instance_assignment, witness_assignment) = match mode {
+        SynthesisMode::Setup => (None, None),
+        _ => {
+            let cs = cs.borrow().ok_or(SynthesisError::MissingCS)?;
+            (Some(strings(&cs.instance_assignment)), Some(strings(&cs.witness_assignment)))
+        }
+    };
+
+    // The instance count includes the constant one
+    Ok(R1csExport {
+        num_variables: matrices.num_instance_variables + matrices.num_witness_variables,
+        num_public_inputs: matrices.num_instance_variables - 1,
+        num_private_variables: matrices.num_witness_variables,
+        constraints,
+        instance_assignment,
+        witness_assignment,
+    })
+}
+
+/// Write the constraint matrices of `circuit` to `out` as pretty-printed JSON
+pub fn export_r1cs_json<C: ConstraintSynthesizer<Fr>>(circuit: C, out: &Path) -> Result<(), ProverError> {
+    let export = r1cs_export(circuit)?;
+    let json = serde_json::to_string_pretty(&export).map_err(std::io::Error::other)?;
+    std::fs::write(out, json)?;
+    println!("📦 Saved R1CS ({} constraints) to: {}", export.constraints.len(), out.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::MulCircuit;
+
+    #[test]
+    fn test_mul_circuit_export_references_its_variables() {
+        let out = std::env::temp_dir().join("poof_mul_r1cs.json");
+        export_r1cs_json(MulCircuit::setup_shape(), &out).expect("export failed");
+        let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+
+        // 0 = one, 1 = c (public), then the witnesses a = 2, b = 3 and the product a * b = 4
+        assert_eq!(json["num_variables"], 5);
+        assert_eq!(json["num_public_inputs"], 1);
+        assert_eq!(json["num_private_variables"], 3);
+
+        let first = &json["constraints"][0];
+        assert_eq!(first["a"], serde_json::json!([[2, "1"]]));
+        assert_eq!(first["b"], serde_json::json!([[3, "1"]]));
+        assert_eq!(first["c"], serde_json::json!([[4, "1"]]));
+        assert!(json.get("witness_assignment").is_none());
+    }
+
+    #[test]
+    fn test_instance_export_has_the_setup_matrices_and_its_assignment() {
+        let export = r1cs_export_with_assignment(MulCircuit::instance(3, 4)).expect("export failed");
+        let shape = r1cs_export(MulCircuit::setup_shape()).expect("export failed");
+
+        assert_eq!(export.constraints, shape.constraints);
+        assert_eq!(export.instance_assignment, Some(vec!["1".to_string(), "12".to_string()]));
+        assert_eq!(export.witness_assignment, Some(["3", "4", "12"].map(String::from).to_vec()));
+    }
+}
@@ -0,0 +1,73 @@
+// Schnorr-style signatures over Poseidon, for statements authenticated inside a SNARK.
+// - key pair: secret key sk, public key pk = H(sk)
+// - signing a message hash m with a fresh nonce k: r = H(k), e = H(r, pk, m), s = k + e * sk
+// Poseidon has no group structure, so unlike Schnorr over a curve nobody can check
+// (r, s) against pk in the clear: a signature is verified by proving knowledge of sk
+// and k satisfying all three equations with `circuit::PoseidonSignatureCircuit`.
+// Never sign two messages with one nonce: s1 - s2 = (e1 - e2) * sk reveals the key.
+
+use ark_bn254::Fr;
+use ark_std::UniformRand;
+use rand::thread_rng;
+use zeroize::Zeroizing;
+
+use crate::poseidon::{poseidon_hash_bytes, poseidon_sponge_hash};
+
+/// Public signature (r, s); the nonce behind r stays with the signer for proving
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoseidonSignature {
+    pub r: Fr,
+    pub s: Fr,
+}
+
+/// Hash an arbitrary byte message to the field element that gets signed
+pub fn message_hash(message: &[u8]) -> Fr {
+    poseidon_hash_bytes(message)
+}
+
+/// The public key committing to `secret_key`
+pub fn public_key(secret_key: Fr) -> Fr {
+    poseidon_sponge_hash(&[secret_key])
+}
+
+/// Fiat-Shamir challenge binding the nonce commitment, the signer and the message
+pub fn challenge(r: Fr, public_key: Fr, message_hash: Fr) -> Fr {
+    poseidon_sponge_hash(&[r, public_key, message_hash])
+}
+
+/// Sign `message_hash` with a fresh random nonce, returned for the signer's proof
+pub fn sign(secret_key: Fr, message_hash: Fr) -> (PoseidonSignature, Zeroizing<Fr>) {
+    let nonce = Zeroizing::new(Fr::rand(&mut thread_rng()));
+    (sign_with_nonce(secret_key, *nonce, message_hash), nonce)
+}
+
+/// Sign with a caller-chosen nonce, which must never be used for another message
+pub fn sign_with_nonce(secret_key: Fr, nonce: Fr, message_hash: Fr) -> PoseidonSignature {
+    let r = poseidon_sponge_hash(&[nonce]);
+    let e = challenge(r, public_key(secret_key), message_hash);
+    PoseidonSignature { r, s: nonce + e * secret_key }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_hash_separates_lengths_and_contents() {
+        assert_ne!(message_hash(b"pay alice"), message_hash(b"pay bob"));
+        assert_ne!(message_hash(b"pay"), message_hash(b"pay\0"));
+        assert_ne!(message_hash(&[7; 31]), message_hash(&[7; 32]));
+    }
+
+    #[test]
+    fn test_nonce_reuse_reveals_the_secret_key() {
+        let (secret_key, nonce) = (Fr::from(1234u64), Fr::from(99u64));
+        let (m1, m2) = (message_hash(b"first"), message_hash(b"second"));
+        let (sig1, sig2) = (sign_with_nonce(secret_key, nonce, m1), sign_with_nonce(secret_key, nonce, m2));
+        assert_eq!(sig1.r, sig2.r);
+
+        let pk = public_key(secret_key);
+        let (e1, e2) = (challenge(sig1.r, pk, m1), challenge(sig2.r, pk, m2));
+        assert_eq!((sig1.s - sig2.s) / (e1 - e2), secret_key);
+    }
+}
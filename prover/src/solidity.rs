@@ -0,0 +1,71 @@
+// Foundry test fixture for a deployed verifier.
+// `generate_solidity_test` writes a `.t.sol` file that sends a proof and its public
+// input, framed as PVM calldata behind the `verifyProofFromCalldata` selector, to the
+// Rust contract through the `VerifyFromSolidity` wrapper (verifier-contract/CallVerifier.sol)
+// and asserts the verifier accepts it. The wrapper and verifier addresses are read from
+// the `WRAPPER_ADDRESS` and `VERIFIER_ADDRESS` environment variables.
+
+use ark_bn254::{Bn254, Fr};
+use ark_groth16::Proof;
+use std::path::Path;
+
+use crate::error::ProverError;
+use crate::utils::{CalldataMode, build_calldata, hex_string};
+
+/// Write a Foundry test for `proof` and `public_input` to `out`
+pub fn generate_solidity_test(proof: &Proof<Bn254>, public_input: &Fr, out: &Path) -> Result<(), ProverError> {
+    std::fs::write(out, solidity_test_source(proof, public_input)?)?;
+    println!("📦 Saved Solidity test to: {}", out.display());
+    Ok(())
+}
+
+/// Source of the test written by `generate_solidity_test`
+pub fn solidity_test_source(proof: &Proof<Bn254>, public_input: &Fr) -> Result<String, ProverError> {
+    let calldata = build_calldata(proof, public_input, CalldataMode::Pvm)?;
+
+    Ok(format!(
+        r#"// SPDX-License-Identifier: Apache-2.0
+// Auto-generated by prover::solidity::generate_solidity_test
+pragma solidity ^0.8.0;
+
+import {{Test}} from "forge-std/Test.sol";
+
+/// `VerifyFromSolidity` from verifier-contract/CallVerifier.sol
+interface IVerifyFromSolidity {{
+    function verify(bytes calldata proofAndInput, address rustContract) external view returns (bool);
+}}
+
+contract VerifierFixtureTest is Test {{
+    // `verifyProofFromCalldata` selector (4 bytes), compressed Groth16 proof (128 bytes)
+    // and big-endian public input (32 bytes): the calldata the Rust contract's `call()` reads
+    bytes constant CALLDATA = hex"{}";
+
+    function test_verifyProofFromCalldata() public view {{
+        IVerifyFromSolidity wrapper = IVerifyFromSolidity(vm.envAddress("WRAPPER_ADDRESS"));
+        assertTrue(wrapper.verify(CALLDATA, vm.envAddress("VERIFIER_ADDRESS")));
+    }}
+}}
+"#,
+        hex_string(&calldata)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate_proof;
+
+    #[test]
+    fn test_generated_test_embeds_calldata_and_assertion() {
+        let (proof, c, _pk) = generate_proof(3, 4).expect("proof generation failed");
+        let out = std::env::temp_dir().join("poof_verifier_fixture.t.sol");
+
+        generate_solidity_test(&proof, &c, &out).expect("writing test failed");
+        let source = std::fs::read_to_string(&out).unwrap();
+
+        let calldata = build_calldata(&proof, &c, CalldataMode::Pvm).unwrap();
+        assert!(calldata.starts_with(&crate::utils::CALLDATA_SELECTOR));
+        assert!(source.contains(&format!("hex\"{}\"", hex_string(&calldata))));
+        assert!(source.contains("assertTrue(wrapper.verify(CALLDATA, vm.envAddress(\"VERIFIER_ADDRESS\")));"));
+    }
+}
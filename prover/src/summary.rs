@@ -0,0 +1,164 @@
+// Log-friendly summary of a Groth16 proof.
+// arkworks' derived `Debug` prints every coordinate; `ProofSummary` prints a
+// truncated hex prefix of each compressed point and the total compressed size.
+// `SizeReport` gives the serialized size of every `MulCircuit` artefact, for sizing
+// calldata and contract storage, and what compression saves on the proof and key.
+
+use ark_bn254::{Bn254, Fr};
+use ark_groth16::Proof;
+use ark_serialize::CanonicalSerialize;
+use std::fmt;
+
+use crate::error::ProverError;
+
+/// Number of bytes of each point shown in the summary
+const PREFIX_BYTES: usize = 4;
+
+pub struct ProofSummary<'a> {
+    proof: &'a Proof<Bn254>,
+}
+
+impl<'a> ProofSummary<'a> {
+    pub fn new(proof: &'a Proof<Bn254>) -> Self {
+        Self { proof }
+    }
+
+    /// Truncated hex of the compressed A, B and C points
+    pub fn prefixes(&self) -> [String; 3] {
+        [
+            hex_prefix(&self.proof.a),
+            hex_prefix(&self.proof.b),
+            hex_prefix(&self.proof.c),
+        ]
+    }
+
+    /// Compressed size of the whole proof in bytes
+    pub fn byte_len(&self) -> usize {
+        self.proof.compressed_size()
+    }
+}
+
+impl fmt::Display for ProofSummary<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a, b, c] = self.prefixes();
+        write!(f, "Proof {{ A: 0x{}…, B: 0x{}…, C: 0x{}…, {} bytes }}", a, b, c, self.byte_len())
+    }
+}
+
+impl fmt::Debug for ProofSummary<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// Byte sizes of the `MulCircuit` artefacts, in both point encodings where they differ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SizeReport {
+    pub proof_compressed: usize,
+    pub proof_uncompressed: usize,
+    pub vk_compressed: usize,
+    pub vk_uncompressed: usize,
+    pub public_input: usize,
+}
+
+/// Sizes measured by serializing a freshly generated `MulCircuit` proof and key
+pub fn serialized_sizes() -> Result<SizeReport, ProverError> {
+    let (proof, c, pk) = crate::generate_proof(3, 4)?;
+    Ok(SizeReport {
+        proof_compressed: proof.compressed_size(),
+        proof_uncompressed: proof.uncompressed_size(),
+        vk_compressed: pk.vk.compressed_size(),
+        vk_uncompressed: pk.vk.uncompressed_size(),
+        public_input: <Fr as CanonicalSerialize>::compressed_size(&c),
+    })
+}
+
+impl SizeReport {
+    /// Bytes saved by the compressed proof encoding
+    pub fn proof_savings(&self) -> usize {
+        self.proof_uncompressed - self.proof_compressed
+    }
+
+    /// Bytes saved by the compressed verifying key encoding
+    pub fn vk_savings(&self) -> usize {
+        self.vk_uncompressed - self.vk_compressed
+    }
+}
+
+// Percentage of `uncompressed` that compression saves, rounded down
+fn percent_saved(saved: usize, uncompressed: usize) -> usize {
+    saved * 100 / uncompressed
+}
+
+impl fmt::Display for SizeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "proof ........... {} bytes compressed, {} uncompressed (saves {} bytes, {}%)",
+            self.proof_compressed,
+            self.proof_uncompressed,
+            self.proof_savings(),
+            percent_saved(self.proof_savings(), self.proof_uncompressed),
+        )?;
+        writeln!(
+            f,
+            "verifying key ... {} bytes compressed, {} uncompressed (saves {} bytes, {}%)",
+            self.vk_compressed,
+            self.vk_uncompressed,
+            self.vk_savings(),
+            percent_saved(self.vk_savings(), self.vk_uncompressed),
+        )?;
+        write!(f, "public input .... {} bytes", self.public_input)
+    }
+}
+
+fn hex_prefix<T: CanonicalSerialize>(point: &T) -> String {
+    let mut bytes = Vec::new();
+    point
+        .serialize_compressed(&mut bytes)
+        .expect("serializing to a Vec cannot fail");
+    crate::utils::hex_string(&bytes[..PREFIX_BYTES])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate_proof;
+
+    #[test]
+    fn test_summary_contains_truncated_prefixes() {
+        let (proof, _c, _pk) = generate_proof(3, 4).expect("proof generation failed");
+
+        let mut bytes = Vec::new();
+        proof.serialize_compressed(&mut bytes).unwrap();
+        let hex = |range: std::ops::Range<usize>| -> String {
+            bytes[range].iter().map(|b| format!("{:02x}", b)).collect()
+        };
+
+        let summary = ProofSummary::new(&proof).to_string();
+        assert!(summary.contains(&format!("A: 0x{}…", hex(0..4))));
+        assert!(summary.contains(&format!("B: 0x{}…", hex(32..36))));
+        assert!(summary.contains(&format!("C: 0x{}…", hex(96..100))));
+        assert!(summary.contains("128 bytes"));
+        assert_eq!(format!("{:?}", ProofSummary::new(&proof)), summary);
+    }
+
+    #[test]
+    fn test_serialized_sizes_match_the_documented_layout() {
+        let sizes = serialized_sizes().expect("measuring sizes failed");
+        assert_eq!(sizes.proof_compressed, 128);
+        assert_eq!(sizes.proof_uncompressed, 256);
+        assert_eq!(sizes.public_input, 32);
+        assert!(sizes.vk_compressed < sizes.vk_uncompressed);
+    }
+
+    #[test]
+    fn test_compression_halves_the_proof() {
+        let sizes = serialized_sizes().expect("measuring sizes failed");
+        // Every point drops its y-coordinate, keeping a flag bit in the x bytes
+        assert_eq!(sizes.proof_savings(), sizes.proof_uncompressed / 2);
+        // The key halves too, apart from the 8-byte length prefix of gamma_abc_g1
+        assert_eq!(sizes.vk_savings() * 2, sizes.vk_uncompressed - 8);
+        assert!(sizes.to_string().contains("(saves 128 bytes, 50%)"));
+    }
+}
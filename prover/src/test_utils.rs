@@ -0,0 +1,83 @@
+// Round-trip assertions shared by the circuit tests, and `corrupt_proof` for
+// systematic rejection tests. Built for this crate's tests and, with the
+// `test-utils` feature, for other crates' tests.
+// Each assertion runs setup on a clone of the circuit (setup never reads assignments),
+// proves with the circuit itself and verifies against the given public inputs.
+
+use ark_bn254::{Bn254, Fr, G1Affine};
+use ark_ec::AffineRepr;
+use ark_groth16::{Groth16, Proof, VerifyingKey};
+use ark_relations::r1cs::ConstraintSynthesizer;
+use ark_snark::SNARK;
+
+fn setup_and_prove<C: ConstraintSynthesizer<Fr> + Clone>(circuit: C) -> (Proof<Bn254>, VerifyingKey<Bn254>) {
+    let mut rng = rand::thread_rng();
+    let (pk, vk) = Groth16::<Bn254>::circuit_specific_setup(circuit.clone(), &mut rng).expect("setup failed");
+    let proof = Groth16::<Bn254>::prove(&pk, circuit, &mut rng).expect("proving failed");
+    (proof, vk)
+}
+
+/// Assert that `circuit` proves and its proof verifies against `public_inputs`; returns the proof
+pub fn assert_proves<C: ConstraintSynthesizer<Fr> + Clone>(circuit: C, public_inputs: &[Fr]) -> Proof<Bn254> {
+    let (proof, vk) = setup_and_prove(circuit);
+    let valid = Groth16::<Bn254>::verify(&vk, public_inputs, &proof).expect("verification could not run");
+    assert!(valid, "proof does not verify against {:?}", public_inputs);
+    proof
+}
+
+/// Assert that the proof of `circuit` does not verify against the wrong `public_inputs`.
+/// `circuit` itself must be satisfiable: arkworks debug-asserts that while proving, so
+/// unsatisfiable assignments are checked with `check_satisfied` instead.
+pub fn assert_rejects<C: ConstraintSynthesizer<Fr> + Clone>(circuit: C, public_inputs: &[Fr]) {
+    let (proof, vk) = setup_and_prove(circuit);
+    let valid = Groth16::<Bn254>::verify(&vk, public_inputs, &proof).expect("verification could not run");
+    assert!(!valid, "proof unexpectedly verifies against {:?}", public_inputs);
+}
+
+/// A well-defined way of damaging a valid proof
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CorruptionKind {
+    /// Replace A with -A: still a valid curve point, but the pairing check fails
+    NegateA,
+    /// Swap the two Fp2 limbs of each of B's coordinates, the usual EVM encoding
+    /// mistake; the result is off the curve
+    SwapB,
+    /// Replace C with the point at infinity
+    ZeroC,
+}
+
+impl CorruptionKind {
+    pub const ALL: [CorruptionKind; 3] = [CorruptionKind::NegateA, CorruptionKind::SwapB, CorruptionKind::ZeroC];
+}
+
+/// A copy of `proof` damaged according to `kind`
+pub fn corrupt_proof(proof: &Proof<Bn254>, kind: CorruptionKind) -> Proof<Bn254> {
+    let mut corrupted = proof.clone();
+    match kind {
+        CorruptionKind::NegateA => corrupted.a = -proof.a,
+        CorruptionKind::SwapB => {
+            std::mem::swap(&mut corrupted.b.x.c0, &mut corrupted.b.x.c1);
+            std::mem::swap(&mut corrupted.b.y.c0, &mut corrupted.b.y.c1);
+        }
+        CorruptionKind::ZeroC => corrupted.c = G1Affine::zero(),
+    }
+    corrupted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_proof, verify_proof};
+
+    #[test]
+    fn test_every_corruption_fails_verification() {
+        let (proof, c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        assert!(verify_proof(&proof, c, &pk.vk).unwrap());
+
+        for kind in CorruptionKind::ALL {
+            let corrupted = corrupt_proof(&proof, kind);
+            assert_ne!(corrupted, proof, "{:?} left the proof unchanged", kind);
+            assert!(!verify_proof(&corrupted, c, &pk.vk).unwrap(), "{:?} proof verified", kind);
+        }
+    }
+}
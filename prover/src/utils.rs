@@ -1,118 +1,1005 @@
 // Utility functions for serializing zkSNARK components to disk.
-// Includes helpers to save:
-// - Verifying key to ../keys/verifying_key.bin
-// - zkSNARK proof to ../proofs/proof.bin
-// - Public input to ../proofs/public_input.bin
-// - calldata to ../calldata.bin
+// Includes helpers to save, relative to a caller-chosen output directory:
+// - Verifying key to <out>/keys/verifying_key.bin
+// - Prepared verifying key to <out>/keys/prepared_verifying_key.bin
+// - zkSNARK proof to <out>/proofs/proof.bin
+// - Public input to <out>/proofs/public_input.bin
+// - Proof in either point encoding to an explicit path, for `zkcli convert-proof`
+// - Proof bundle (proof, public input and verifying key) to <out>/proofs/bundle.bin
+// - Many proofs in one file (a count, then each compressed proof) to an explicit path
+// - calldata to an explicit path, with public inputs as big- or little-endian words
+// Key, proof and input files start with a one-byte `ARTIFACT_VERSION` tag that the
+// matching `load_*` functions check before deserializing. Proof files then carry a
+// curve tag and the arkworks serialization format they were written with. Proof, public input and
+// verifying key loaders also refuse a payload of a size the type cannot have.
+// - proving key as numbered chunks plus a manifest, for storage backends that dislike large files
+// The `*_with_writer` variants take a `Writer`, so `zkcli --dry-run` can list what a
+// command would write without creating any files, and an `ArtifactDirs`, so keys and
+// proofs can live in unrelated directories.
 
 use ark_bn254::{Fr};
-use ark_groth16::{Proof, ProvingKey, VerifyingKey};
+use ark_groth16::{PreparedVerifyingKey, Proof, ProvingKey, VerifyingKey};
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
 use ark_ff::PrimeField;
 use std::fs::File;
 use std::io::Write;
-use ark_serialize::CanonicalSerialize;
+use std::path::{Path, PathBuf};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError, Validate};
 use ark_ff::BigInteger;
+use sha2::{Digest, Sha256};
 
+// The contract's compile-time selector helper, shared so both sides derive the same bytes
+#[path = "../../verifier-contract/src/selector.rs"]
+mod selector;
 
-pub fn save_proving_key(pk: &ProvingKey<ark_bn254::Bn254>) -> std::io::Result<()> {
-    let mut file = File::create("../keys/proving_key.bin")?;
-    pk.serialize_uncompressed(&mut file)
-        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+use crate::bundle::ProofBundle;
+use crate::calldata::PROOF_LEN;
+use crate::error::ProverError;
+
+const MANIFEST_FILE: &str = "manifest.txt";
+
+/// Version tag written in front of every key, proof and public input file.
+/// Version 2 added the `frame_proof` tags to proof files; version 1 files still load.
+pub const ARTIFACT_VERSION: u8 = 2;
+
+/// Curve tag framing every proof file; BN254 is the only curve this crate proves over
+pub const CURVE_BN254: u8 = 1;
+
+/// Serialization format tag framing every proof file: proofs are written by ark-serialize 0.4,
+/// whose point encoding earlier and later releases do not promise to keep
+pub const ARK_SERIALIZE_FORMAT: u8 = 4;
+
+/// Subdirectory of the output directory holding keys
+pub const KEYS_DIR: &str = "keys";
+/// Subdirectory of the output directory holding proofs and public inputs
+pub const PROOFS_DIR: &str = "proofs";
+
+/// Where the `*_with_writer` save and export functions send their bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Writer {
+    /// Create the file, and any missing parent directories
+    #[default]
+    Disk,
+    /// Create nothing; print the path and byte count that would have been written
+    DryRun,
+}
+
+impl Writer {
+    pub fn write(self, path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+        match self {
+            Writer::Disk => {
+                if let Some(dir) = path.parent() {
+                    std::fs::create_dir_all(dir)?;
+                }
+                let mut file = File::create(path)?;
+                file.write_all(bytes)
+            }
+            Writer::DryRun => {
+                println!("📝 Would write {} bytes to: {}", bytes.len(), path.display());
+                Ok(())
+            }
+        }
+    }
+
+    // Progress messages only describe files that were actually written
+    fn report(self, message: std::fmt::Arguments<'_>) {
+        if self == Writer::Disk {
+            println!("{}", message);
+        }
+    }
+}
+
+/// Directories the `*_with_writer` save functions write keys and proofs to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArtifactDirs {
+    pub keys: PathBuf,
+    pub proofs: PathBuf,
+}
+
+impl ArtifactDirs {
+    /// `<out_dir>/keys` and `<out_dir>/proofs`, the layout of the plain save functions
+    pub fn under(out_dir: &Path) -> Self {
+        ArtifactDirs { keys: out_dir.join(KEYS_DIR), proofs: out_dir.join(PROOFS_DIR) }
+    }
+}
+
+// `<out_dir>/<subdir>/<file>`; the writer creates the subdirectory
+fn artifact_path(out_dir: &Path, subdir: &str, file: &str) -> PathBuf {
+    out_dir.join(subdir).join(file)
+}
+
+fn write_versioned(writer: Writer, path: &Path, payload: &[u8]) -> std::io::Result<()> {
+    let mut bytes = Vec::with_capacity(1 + payload.len());
+    bytes.push(ARTIFACT_VERSION);
+    bytes.extend_from_slice(payload);
+    writer.write(path, &bytes)
+}
+
+/// Read an artefact file, check its version tag and return the payload after it
+pub fn read_versioned(path: &Path) -> Result<Vec<u8>, ProverError> {
+    let (version, payload) = split_version(std::fs::read(path)?)?;
+    migrate(version, payload)
+}
+
+/// `read_versioned` for proof files, whose layout changed in version 2: the payload comes
+/// back with its `frame_proof` tags whichever version wrote it
+pub fn read_versioned_proof(path: &Path) -> Result<Vec<u8>, ProverError> {
+    let (version, payload) = split_version(std::fs::read(path)?)?;
+    migrate_proof(version, payload)
+}
+
+fn split_version(mut bytes: Vec<u8>) -> Result<(u8, Vec<u8>), ProverError> {
+    if bytes.is_empty() {
+        return Err(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "artefact file is empty").into());
+    }
+    let version = bytes.remove(0);
+    Ok((version, bytes))
+}
+
+/// Bring a payload saved under `version` up to the current `ARTIFACT_VERSION` layout.
+/// Version 2 changed only proof files, which go through `migrate_proof`; every other
+/// artefact reads the same under both versions.
+pub fn migrate(version: u8, payload: Vec<u8>) -> Result<Vec<u8>, ProverError> {
+    match version {
+        1 | ARTIFACT_VERSION => Ok(payload),
+        found => Err(ProverError::UnsupportedVersion { found, expected: ARTIFACT_VERSION }),
+    }
+}
+
+/// `migrate` for proof payloads. Version 1 proofs predate the `frame_proof` tags; every
+/// one was written by this crate for BN254 with ark-serialize 0.4, so they gain exactly those.
+pub fn migrate_proof(version: u8, payload: Vec<u8>) -> Result<Vec<u8>, ProverError> {
+    match version {
+        1 => Ok(frame_proof(&payload)),
+        version => migrate(version, payload),
+    }
+}
+
+pub fn save_proving_key(pk: &ProvingKey<ark_bn254::Bn254>, out_dir: &Path) -> std::io::Result<PathBuf> {
+    let out_path = artifact_path(out_dir, KEYS_DIR, "proving_key.bin");
+    let mut buf = Vec::new();
+    pk.serialize_uncompressed(&mut buf)
+        .map_err(std::io::Error::other)?;
+    write_versioned(Writer::Disk, &out_path, &buf)?;
+    Ok(out_path)
+}
+
+pub fn load_proving_key(path: &Path) -> Result<ProvingKey<ark_bn254::Bn254>, ProverError> {
+    let payload = read_versioned(path)?;
+    Ok(ProvingKey::deserialize_uncompressed(&payload[..])?)
+}
+
+/// Write the uncompressed proving key to `dir` as `proving_key.NNN.bin` chunks of at most
+/// `chunk_bytes` bytes, plus a `manifest.txt` recording the chunk count, size and SHA-256.
+pub fn save_proving_key_chunked(
+    pk: &ProvingKey<ark_bn254::Bn254>,
+    dir: &Path,
+    chunk_bytes: usize,
+) -> std::io::Result<()> {
+    if chunk_bytes == 0 {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "chunk size must be non-zero"));
+    }
+
+    let mut buf = Vec::new();
+    pk.serialize_uncompressed(&mut buf)
+        .map_err(std::io::Error::other)?;
+
+    std::fs::create_dir_all(dir)?;
+    let chunks: Vec<&[u8]> = buf.chunks(chunk_bytes).collect();
+    for (i, chunk) in chunks.iter().enumerate() {
+        std::fs::write(dir.join(chunk_file_name(i)), chunk)?;
+    }
+
+    let manifest = format!(
+        "chunks={}\ntotal_bytes={}\nsha256={}\n",
+        chunks.len(),
+        buf.len(),
+        hex_string(&Sha256::digest(&buf)),
+    );
+    std::fs::write(dir.join(MANIFEST_FILE), manifest)?;
+
+    println!("📦 Saved proving key ({} bytes, {} chunks) to: {}", buf.len(), chunks.len(), dir.display());
     Ok(())
 }
 
-pub fn save_verifying_key(vk: &VerifyingKey<ark_bn254::Bn254>) -> std::io::Result<()> {
-    let out_path = "../keys/verifying_key.bin";
+/// Reassemble a proving key written by `save_proving_key_chunked`, checking the manifest checksum
+pub fn load_proving_key_chunked(dir: &Path) -> std::io::Result<ProvingKey<ark_bn254::Bn254>> {
+    let invalid = |msg: String| std::io::Error::new(std::io::ErrorKind::InvalidData, msg);
+
+    let manifest = std::fs::read_to_string(dir.join(MANIFEST_FILE))?;
+    let field = |key: &str| -> std::io::Result<&str> {
+        manifest
+            .lines()
+            .find_map(|line| line.strip_prefix(key).and_then(|rest| rest.strip_prefix('=')))
+            .ok_or_else(|| invalid(format!("manifest is missing `{}`", key)))
+    };
+    let chunks: usize = field("chunks")?.parse().map_err(|e| invalid(format!("bad chunk count: {}", e)))?;
+    let total_bytes: usize = field("total_bytes")?.parse().map_err(|e| invalid(format!("bad total size: {}", e)))?;
+    let expected_sha = field("sha256")?;
+
+    // The manifest is not authenticated until the checksum matches, so its size only bounds the read
+    let mut buf = Vec::new();
+    for i in 0..chunks {
+        buf.extend_from_slice(&std::fs::read(dir.join(chunk_file_name(i)))?);
+        if buf.len() > total_bytes {
+            return Err(invalid(format!("chunks hold more than the {} bytes the manifest lists", total_bytes)));
+        }
+    }
+
+    if buf.len() != total_bytes {
+        return Err(invalid(format!("expected {} bytes of proving key, found {}", total_bytes, buf.len())));
+    }
+    if hex_string(&Sha256::digest(&buf)) != expected_sha {
+        return Err(invalid("proving key checksum does not match manifest".to_string()));
+    }
+
+    ProvingKey::<ark_bn254::Bn254>::deserialize_uncompressed(&buf[..])
+        .map_err(std::io::Error::other)
+}
+
+fn chunk_file_name(index: usize) -> String {
+    format!("proving_key.{:03}.bin", index)
+}
+
+/// Lowercase hex without a `0x` prefix
+pub fn hex_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn save_verifying_key(vk: &VerifyingKey<ark_bn254::Bn254>, out_dir: &Path) -> std::io::Result<PathBuf> {
+    save_verifying_key_with_writer(vk, &ArtifactDirs::under(out_dir), Writer::Disk)
+}
+
+pub fn save_verifying_key_with_writer(
+    vk: &VerifyingKey<ark_bn254::Bn254>,
+    dirs: &ArtifactDirs,
+    writer: Writer,
+) -> std::io::Result<PathBuf> {
+    let out_path = dirs.keys.join("verifying_key.bin");
 
     let mut buf = Vec::new();
     vk.serialize_uncompressed(&mut buf)
-        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        .map_err(std::io::Error::other)?;
 
-    println!("📦 Saved verifying key ({} bytes) to: {}", buf.len(), out_path);
+    writer.report(format_args!("📦 Saved verifying key ({} bytes) to: {}", buf.len(), out_path.display()));
 
-    let mut file = File::create(out_path)?;
-    file.write_all(&buf)?;
-    Ok(())
+    write_versioned(writer, &out_path, &buf)?;
+    Ok(out_path)
 }
 
 
-pub fn save_proof(proof: &Proof<ark_bn254::Bn254>) -> std::io::Result<()> {
+pub fn load_verifying_key(path: &Path) -> Result<VerifyingKey<ark_bn254::Bn254>, ProverError> {
+    let payload = read_versioned(path)?;
+    // alpha_g1 (64), three G2 points (3 * 128) and the gamma_abc_g1 length prefix (8),
+    // then 64 bytes per gamma_abc_g1 point, of which there is at least one
+    check_artifact_size("verifying key", "456 + 64 * n", &payload, |len| {
+        len >= 456 + 64 && (len - 456).is_multiple_of(64)
+    })?;
+    Ok(VerifyingKey::deserialize_uncompressed(&payload[..])?)
+}
+
+pub fn save_prepared_verifying_key(
+    pvk: &PreparedVerifyingKey<ark_bn254::Bn254>,
+    out_dir: &Path,
+) -> std::io::Result<PathBuf> {
+    save_prepared_verifying_key_with_writer(pvk, &ArtifactDirs::under(out_dir), Writer::Disk)
+}
+
+pub fn save_prepared_verifying_key_with_writer(
+    pvk: &PreparedVerifyingKey<ark_bn254::Bn254>,
+    dirs: &ArtifactDirs,
+    writer: Writer,
+) -> std::io::Result<PathBuf> {
+    let out_path = dirs.keys.join("prepared_verifying_key.bin");
+
+    let mut buf = Vec::new();
+    pvk.serialize_uncompressed(&mut buf)
+        .map_err(std::io::Error::other)?;
+
+    writer.report(format_args!("📦 Saved prepared verifying key ({} bytes) to: {}", buf.len(), out_path.display()));
+
+    write_versioned(writer, &out_path, &buf)?;
+    Ok(out_path)
+}
+
+pub fn load_prepared_verifying_key(path: &Path) -> Result<PreparedVerifyingKey<ark_bn254::Bn254>, ProverError> {
+    let payload = read_versioned(path)?;
+    Ok(PreparedVerifyingKey::deserialize_uncompressed(&payload[..])?)
+}
+
+/// Validates the proof points before writing; see `save_proof_with_validation`
+pub fn save_proof(proof: &Proof<ark_bn254::Bn254>, out_dir: &Path) -> Result<PathBuf, ProverError> {
+    save_proof_with_validation(proof, out_dir, true)
+}
+
+/// With `validate`, a proof whose A, B or C is off the curve or outside the
+/// prime-order subgroup is refused with `ProverError::InvalidPoint` instead of
+/// being written and only failing at verification time.
+pub fn save_proof_with_validation(
+    proof: &Proof<ark_bn254::Bn254>,
+    out_dir: &Path,
+    validate: bool,
+) -> Result<PathBuf, ProverError> {
+    write_proof_file(proof, &ArtifactDirs::under(out_dir), validate, Writer::Disk)
+}
+
+/// As `save_proof`, writing to `dirs.proofs` through `writer`
+pub fn save_proof_with_writer(
+    proof: &Proof<ark_bn254::Bn254>,
+    dirs: &ArtifactDirs,
+    writer: Writer,
+) -> Result<PathBuf, ProverError> {
+    write_proof_file(proof, dirs, true, writer)
+}
+
+fn write_proof_file(
+    proof: &Proof<ark_bn254::Bn254>,
+    dirs: &ArtifactDirs,
+    validate: bool,
+    writer: Writer,
+) -> Result<PathBuf, ProverError> {
+    if validate {
+        check_proof_points(proof)?;
+    }
+
     let mut buf = Vec::new();
     proof.serialize_compressed(&mut buf)
-        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        .map_err(std::io::Error::other)?;
 
-    let out_path = "../proofs/proof.bin";
-    println!("🔍 Compressed proof size: {} bytes", buf.len());
-    println!("📦 Saved proof to: {}", out_path);
+    let out_path = dirs.proofs.join("proof.bin");
+    writer.report(format_args!("🔍 Compressed proof size: {} bytes", buf.len()));
+    writer.report(format_args!("📦 Saved proof to: {}", out_path.display()));
 
-    let mut file = File::create(out_path)?;
-    file.write_all(&buf)?;
-    Ok(())
+    write_versioned(writer, &out_path, &frame_proof(&buf))?;
+    Ok(out_path)
+}
+
+/// Prefix an encoded proof with the curve and serialization format tags
+pub fn frame_proof(proof_bytes: &[u8]) -> Vec<u8> {
+    let mut framed = Vec::with_capacity(2 + proof_bytes.len());
+    framed.extend_from_slice(&[CURVE_BN254, ARK_SERIALIZE_FORMAT]);
+    framed.extend_from_slice(proof_bytes);
+    framed
+}
+
+/// Check the tags written by `frame_proof` and return the encoded proof after them
+pub fn unframe_proof(framed: &[u8]) -> Result<&[u8], ProverError> {
+    let Some((&[curve, format], proof_bytes)) = framed.split_first_chunk::<2>() else {
+        return Err(SerializationError::InvalidData.into());
+    };
+    if curve != CURVE_BN254 {
+        return Err(ProverError::IncompatibleFormat { tag: "curve", found: curve, expected: CURVE_BN254 });
+    }
+    if format != ARK_SERIALIZE_FORMAT {
+        return Err(ProverError::IncompatibleFormat {
+            tag: "serialization format",
+            found: format,
+            expected: ARK_SERIALIZE_FORMAT,
+        });
+    }
+    Ok(proof_bytes)
+}
+
+fn check_proof_points(proof: &Proof<ark_bn254::Bn254>) -> Result<(), ProverError> {
+    let checks = [("A", point_is_valid(&proof.a)), ("B", point_is_valid(&proof.b)), ("C", point_is_valid(&proof.c))];
+    match checks.iter().find(|(_, valid)| !valid) {
+        Some((name, _)) => Err(ProverError::InvalidPoint(format!(
+            "proof point {} is off the curve or outside the prime-order subgroup",
+            name
+        ))),
+        None => Ok(()),
+    }
+}
+
+fn point_is_valid<P: SWCurveConfig>(point: &Affine<P>) -> bool {
+    point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve()
+}
+
+/// Accepts either encoding, so proofs rewritten by `save_proof_as` load too
+pub fn load_proof(path: &Path) -> Result<Proof<ark_bn254::Bn254>, ProverError> {
+    let payload = read_versioned_proof(path)?;
+    check_artifact_size("proof", "130 or 258", &payload, |len| len == 130 || len == 258)?;
+    Ok(decode_proof(unframe_proof(&payload)?)?.0)
+}
+
+// Catches a file of the wrong type before arkworks fails on it with a less telling error
+fn check_artifact_size(
+    artifact: &'static str,
+    expected_size: &'static str,
+    payload: &[u8],
+    fits: impl Fn(usize) -> bool,
+) -> Result<(), ProverError> {
+    if fits(payload.len()) {
+        Ok(())
+    } else {
+        Err(ProverError::WrongArtifactType { artifact, expected_size, actual_size: payload.len() })
+    }
+}
+
+/// Point encoding of a serialized proof
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofEncoding {
+    /// x-coordinates plus flag bits, as written by `save_proof` and read by the PVM contract (128 bytes)
+    Compressed,
+    /// Both coordinates of every point (256 bytes)
+    Uncompressed,
+}
+
+impl ProofEncoding {
+    fn compress(self) -> Compress {
+        match self {
+            ProofEncoding::Compressed => Compress::Yes,
+            ProofEncoding::Uncompressed => Compress::No,
+        }
+    }
+}
+
+pub fn encode_proof(proof: &Proof<ark_bn254::Bn254>, encoding: ProofEncoding) -> Vec<u8> {
+    let mut buf = Vec::new();
+    proof
+        .serialize_with_mode(&mut buf, encoding.compress())
+        .expect("serializing to a Vec cannot fail");
+    buf
+}
+
+/// Deserialize a proof payload, detecting its encoding by trying both.
+/// An encoding only matches if it consumes the whole payload.
+pub fn decode_proof(bytes: &[u8]) -> Result<(Proof<ark_bn254::Bn254>, ProofEncoding), ProverError> {
+    for encoding in [ProofEncoding::Compressed, ProofEncoding::Uncompressed] {
+        let compress = encoding.compress();
+        if let Ok(proof) = Proof::deserialize_with_mode(bytes, compress, Validate::Yes)
+            && proof.serialized_size(compress) == bytes.len()
+        {
+            return Ok((proof, encoding));
+        }
+    }
+    Err(SerializationError::InvalidData.into())
+}
+
+/// Write a versioned proof file in the given encoding
+pub fn save_proof_as(proof: &Proof<ark_bn254::Bn254>, path: &Path, encoding: ProofEncoding) -> std::io::Result<()> {
+    save_proof_as_with_writer(proof, path, encoding, Writer::Disk)
+}
+
+pub fn save_proof_as_with_writer(
+    proof: &Proof<ark_bn254::Bn254>,
+    path: &Path,
+    encoding: ProofEncoding,
+    writer: Writer,
+) -> std::io::Result<()> {
+    write_versioned(writer, path, &frame_proof(&encode_proof(proof, encoding)))
+}
+
+/// Write several proofs to one versioned file for batch verification: the proof count as
+/// a little-endian u64, then each compressed proof
+pub fn save_proofs(proofs: &[Proof<ark_bn254::Bn254>], path: &Path) -> std::io::Result<()> {
+    let mut buf = Vec::with_capacity(8 + PROOF_LEN * proofs.len());
+    buf.extend_from_slice(&(proofs.len() as u64).to_le_bytes());
+    for proof in proofs {
+        buf.extend_from_slice(&encode_proof(proof, ProofEncoding::Compressed));
+    }
+
+    println!("📦 Saved {} proofs ({} bytes) to: {}", proofs.len(), buf.len(), path.display());
+
+    write_versioned(Writer::Disk, path, &buf)
+}
+
+/// Read a file written by `save_proofs`. A count that disagrees with the number of
+/// proofs in the file is refused before any proof is decoded.
+pub fn load_proofs(path: &Path) -> Result<Vec<Proof<ark_bn254::Bn254>>, ProverError> {
+    let payload = read_versioned(path)?;
+    let count = payload.first_chunk::<8>().map(|count| u64::from_le_bytes(*count));
+    check_artifact_size("proof list", "8 + 128 * count", &payload, |len| {
+        let expected = count.and_then(|n| usize::try_from(n).ok()?.checked_mul(PROOF_LEN)?.checked_add(8));
+        expected == Some(len)
+    })?;
+    payload[8..]
+        .chunks(PROOF_LEN)
+        .map(|bytes| Ok(Proof::deserialize_compressed(bytes)?))
+        .collect()
 }
 
-pub fn save_public_input(c: &Fr) -> std::io::Result<()> {
-    let out_path = "../proofs/public_input.bin";
+pub fn save_public_input(c: &Fr, out_dir: &Path) -> std::io::Result<PathBuf> {
+    save_public_input_with_writer(c, &ArtifactDirs::under(out_dir), Writer::Disk)
+}
+
+pub fn save_public_input_with_writer(c: &Fr, dirs: &ArtifactDirs, writer: Writer) -> std::io::Result<PathBuf> {
+    let out_path = dirs.proofs.join("public_input.bin");
 
     let mut buf = Vec::new();
     c.serialize_uncompressed(&mut buf)
-        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        .map_err(std::io::Error::other)?;
 
-    println!("📦 Saved public input ({} bytes) to: {}", buf.len(), out_path);
+    writer.report(format_args!("📦 Saved public input ({} bytes) to: {}", buf.len(), out_path.display()));
 
-    let mut file = File::create(out_path)?;
-    file.write_all(&buf)?;
-    Ok(())
+    write_versioned(writer, &out_path, &buf)?;
+    Ok(out_path)
+}
+
+pub fn load_public_input(path: &Path) -> Result<Fr, ProverError> {
+    let payload = read_versioned(path)?;
+    check_artifact_size("public input", "32", &payload, |len| len == 32)?;
+    Ok(Fr::deserialize_uncompressed(&payload[..])?)
 }
 
+pub fn save_bundle(bundle: &ProofBundle, out_dir: &Path) -> std::io::Result<PathBuf> {
+    let out_path = artifact_path(out_dir, PROOFS_DIR, "bundle.bin");
+
+    let mut buf = Vec::new();
+    bundle.serialize_compressed(&mut buf)
+        .map_err(std::io::Error::other)?;
+
+    println!("📦 Saved proof bundle ({} bytes) to: {}", buf.len(), out_path.display());
+
+    write_versioned(Writer::Disk, &out_path, &buf)?;
+    Ok(out_path)
+}
+
+pub fn load_bundle(path: &Path) -> Result<ProofBundle, ProverError> {
+    let payload = read_versioned(path)?;
+    Ok(ProofBundle::deserialize_compressed(&payload[..])?)
+}
 
 fn wrap_serialize_error<E: std::fmt::Display>(err: E) -> std::io::Error {
-    std::io::Error::new(std::io::ErrorKind::Other, format!("{}", err))
+    std::io::Error::other(format!("{}", err))
 }
 
-pub fn save_calldata<F: PrimeField>(
+/// Calldata framing produced by `build_calldata`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CalldataMode {
+    /// Selector followed by the flat payload, as read by the PVM contract (164 bytes)
+    #[default]
+    Pvm,
+    /// Selector, then the payload ABI-encoded as a single Solidity `bytes` argument
+    /// (offset word, length word, payload padded to 32 bytes)
+    SolidityAbi,
+    /// Just the payload: compressed proof followed by the public input (160 bytes)
+    Raw,
+}
+
+/// Signature of the verifier function whose selector fronts framed calldata
+pub const CALLDATA_SIGNATURE: &str = "verifyProofFromCalldata(bytes)";
+
+/// 4-byte selector placed in front of framed calldata: `keccak256(CALLDATA_SIGNATURE)[..4]`
+pub const CALLDATA_SELECTOR: [u8; 4] = selector::function_selector(CALLDATA_SIGNATURE);
+
+/// Byte order of the 32-byte public input words in calldata
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Endian {
+    /// Most significant byte first, as the EVM and the PVM contract read words
+    #[default]
+    Big,
+    /// Least significant byte first, as arkworks serializes field elements
+    Little,
+}
+
+/// A field element as a 32-byte word in the given byte order
+pub fn field_element_to_32_bytes<F: PrimeField>(f: &F, endian: Endian) -> [u8; 32] {
+    let bytes = match endian {
+        Endian::Big => f.into_bigint().to_bytes_be(),
+        Endian::Little => f.into_bigint().to_bytes_le(),
+    };
+    let mut word = [0u8; 32];
+    word.copy_from_slice(&bytes);
+    word
+}
+
+/// Build calldata from a proof and its big-endian public input, framed according to `mode`
+pub fn build_calldata<F: PrimeField>(
     proof: &Proof<ark_bn254::Bn254>,
     public_input: &F,
-    path: &str,
-) -> std::io::Result<()> {
-    let mut buf = Vec::new();
+    mode: CalldataMode,
+) -> std::io::Result<Vec<u8>> {
+    build_calldata_with_inputs(proof, std::slice::from_ref(public_input), mode)
+}
 
-    // 4-byte dummy selector
-    buf.extend_from_slice(&[0u8; 4]);
+/// As `build_calldata`, for a circuit with several public inputs: each follows the
+/// proof as a big-endian 32-byte word, in the order the circuit allocates them
+pub fn build_calldata_with_inputs<F: PrimeField>(
+    proof: &Proof<ark_bn254::Bn254>,
+    public_inputs: &[F],
+    mode: CalldataMode,
+) -> std::io::Result<Vec<u8>> {
+    build_calldata_with_endian(proof, public_inputs, mode, Endian::Big)
+}
+
+/// As `build_calldata_with_inputs`, writing each public input word in `endian` byte order
+/// for chains and precompiles that expect little-endian field elements
+pub fn build_calldata_with_endian<F: PrimeField>(
+    proof: &Proof<ark_bn254::Bn254>,
+    public_inputs: &[F],
+    mode: CalldataMode,
+    endian: Endian,
+) -> std::io::Result<Vec<u8>> {
+    let mut payload = Vec::new();
 
     // Compress and serialize Groth16 proof
     let mut proof_bytes = Vec::new();
     proof.serialize_compressed(&mut proof_bytes).map_err(wrap_serialize_error)?;
     assert_eq!(proof_bytes.len(), 128);
-    buf.extend_from_slice(&proof_bytes);
+    payload.extend_from_slice(&proof_bytes);
 
-    // Serialize public input
-    let bytes = public_input.into_bigint().to_bytes_be();
-    assert_eq!(bytes.len(), 32);
-    buf.extend_from_slice(&bytes);
+    // Serialize public inputs
+    for input in public_inputs {
+        payload.extend_from_slice(&field_element_to_32_bytes(input, endian));
+    }
 
-    // Final length check: 4 (selector) + 128 (proof) + 32 (input) = 164
-    assert_eq!(buf.len(), 164);
+    // Payload: 128 (proof) + 32 per input, 160 for a single input
+    assert_eq!(payload.len(), 128 + 32 * public_inputs.len());
 
-    let mut file = File::create(path)?;
-    file.write_all(&buf)?;
+    let buf = match mode {
+        CalldataMode::Raw => payload,
+        CalldataMode::Pvm => {
+            // 4 (selector) + payload, 164 for a single input
+            let mut buf = CALLDATA_SELECTOR.to_vec();
+            buf.extend_from_slice(&payload);
+            buf
+        }
+        CalldataMode::SolidityAbi => {
+            // 4 (selector) + 32 (offset) + 32 (length) + payload padded to a 32-byte multiple
+            let mut buf = CALLDATA_SELECTOR.to_vec();
+            buf.extend_from_slice(&abi_word(32));
+            buf.extend_from_slice(&abi_word(payload.len()));
+            buf.extend_from_slice(&payload);
+            buf.resize(buf.len() + (32 - payload.len() % 32) % 32, 0);
+            buf
+        }
+    };
 
-    println!("📦 Saved calldata ({} bytes) to: {}", buf.len(), path);
+    Ok(buf)
+}
 
-    Ok(())
+// usize as a big-endian 32-byte ABI word
+fn abi_word(value: usize) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&(value as u64).to_be_bytes());
+    word
 }
 
+pub fn save_calldata<F: PrimeField>(
+    proof: &Proof<ark_bn254::Bn254>,
+    public_input: &F,
+    path: &Path,
+    mode: CalldataMode,
+) -> std::io::Result<()> {
+    save_calldata_with_inputs(proof, std::slice::from_ref(public_input), path, mode)
+}
 
-pub fn export_verifying_key_to_rs(
-    vk: &VerifyingKey<ark_bn254::Bn254>
+pub fn save_calldata_with_writer<F: PrimeField>(
+    proof: &Proof<ark_bn254::Bn254>,
+    public_input: &F,
+    path: &Path,
+    mode: CalldataMode,
+    writer: Writer,
 ) -> std::io::Result<()> {
-    let mut buf = Vec::new();
-    vk.serialize_compressed(&mut buf)
-        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
-
-    std::fs::create_dir_all("../keys")?;
-    std::fs::write(
-        "../keys/verifying_key_bytes.rs",
-        format!("pub const VERIFYING_KEY_BYTES: &[u8] = &{:?};", buf),
-    )?;
+    write_calldata(proof, std::slice::from_ref(public_input), path, mode, writer)
+}
+
+/// As `save_calldata`, for a circuit with several public inputs
+pub fn save_calldata_with_inputs<F: PrimeField>(
+    proof: &Proof<ark_bn254::Bn254>,
+    public_inputs: &[F],
+    path: &Path,
+    mode: CalldataMode,
+) -> std::io::Result<()> {
+    write_calldata(proof, public_inputs, path, mode, Writer::Disk)
+}
+
+fn write_calldata<F: PrimeField>(
+    proof: &Proof<ark_bn254::Bn254>,
+    public_inputs: &[F],
+    path: &Path,
+    mode: CalldataMode,
+    writer: Writer,
+) -> std::io::Result<()> {
+    let buf = build_calldata_with_inputs(proof, public_inputs, mode)?;
+
+    writer.write(path, &buf)?;
+
+    writer.report(format_args!("📦 Saved calldata ({} bytes) to: {}", buf.len(), path.display()));
+    writer.report(format_args!("   0x{}", hex_string(&buf)));
+
     Ok(())
 }
+
+
+/// Write `<out>/keys/verifying_key_bytes.rs` in the uncompressed encoding the PVM contract `include!`s
+pub fn export_verifying_key_to_rs(
+    vk: &VerifyingKey<ark_bn254::Bn254>,
+    out_dir: &Path,
+) -> std::io::Result<PathBuf> {
+    export_verifying_key_to_rs_with_writer(vk, &ArtifactDirs::under(out_dir), Writer::Disk)
+}
+
+pub fn export_verifying_key_to_rs_with_writer(
+    vk: &VerifyingKey<ark_bn254::Bn254>,
+    dirs: &ArtifactDirs,
+    writer: Writer,
+) -> std::io::Result<PathBuf> {
+    let source = crate::verifying_key_rs_source(vk).map_err(std::io::Error::other)?;
+
+    let out_path = dirs.keys.join("verifying_key_bytes.rs");
+    writer.write(&out_path, source.as_bytes())?;
+    Ok(out_path)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate_proof;
+
+    #[test]
+    fn test_raw_calldata_is_just_the_payload() {
+        let (proof, c, _pk) = generate_proof(3, 4).expect("proof generation failed");
+        let raw = build_calldata(&proof, &c, CalldataMode::Raw).expect("calldata build failed");
+        let pvm = build_calldata(&proof, &c, CalldataMode::Pvm).expect("calldata build failed");
+
+        assert_eq!(raw.len(), 160);
+        assert_eq!(pvm.len(), 164);
+        assert_eq!(&pvm[4..], &raw[..]);
+    }
+
+    #[test]
+    fn test_calldata_selector_is_keccak_of_the_signature() {
+        use sha3::{Digest, Keccak256};
+        assert_eq!(CALLDATA_SELECTOR[..], Keccak256::digest(CALLDATA_SIGNATURE.as_bytes())[..4]);
+        // Signatures across the 136-byte block boundary need a second permutation
+        for len in [0, 135, 136, 137, 300] {
+            let signature = "f".repeat(len);
+            assert_eq!(selector::function_selector(&signature)[..], Keccak256::digest(signature.as_bytes())[..4]);
+        }
+    }
+
+    #[test]
+    fn test_abi_calldata_has_selector_offset_and_length_prefix() {
+        let (proof, c, _pk) = generate_proof(3, 4).expect("proof generation failed");
+        let raw = build_calldata(&proof, &c, CalldataMode::Raw).expect("calldata build failed");
+        let abi = build_calldata(&proof, &c, CalldataMode::SolidityAbi).expect("calldata build failed");
+
+        assert_eq!(abi.len(), 68 + raw.len());
+        assert_eq!(&abi[..4], &CALLDATA_SELECTOR);
+        assert_eq!(abi[4..36], abi_word(32));
+        assert_eq!(abi[36..68], abi_word(raw.len()));
+        assert_eq!(&abi[68..], &raw[..]);
+    }
+
+    #[test]
+    fn test_field_element_encodings_are_byte_reversals() {
+        let f = Fr::from(0x0102_0304u64);
+        let be = field_element_to_32_bytes(&f, Endian::Big);
+        let le = field_element_to_32_bytes(&f, Endian::Little);
+
+        assert_eq!(be[28..], [0x01, 0x02, 0x03, 0x04]);
+        assert_eq!(le[..4], [0x04, 0x03, 0x02, 0x01]);
+        assert!(be[..28].iter().chain(&le[4..]).all(|&b| b == 0));
+
+        let (proof, c, _pk) = generate_proof(3, 4).expect("proof generation failed");
+        let big = build_calldata(&proof, &c, CalldataMode::Raw).expect("calldata build failed");
+        let little = build_calldata_with_endian(&proof, &[c], CalldataMode::Raw, Endian::Little)
+            .expect("calldata build failed");
+        assert_eq!(big[..128], little[..128]);
+        assert_eq!(little[128..], field_element_to_32_bytes(&c, Endian::Little));
+    }
+
+    #[test]
+    fn test_chunked_proving_key_round_trip() {
+        let (_proof, _c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let dir = std::env::temp_dir().join("poof_chunked_pk_round_trip");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        save_proving_key_chunked(&pk, &dir, 512).expect("chunked save failed");
+        assert!(dir.join(chunk_file_name(1)).exists(), "expected more than one chunk");
+
+        let loaded = load_proving_key_chunked(&dir).expect("chunked load failed");
+        assert_eq!(loaded, pk);
+    }
+
+    #[test]
+    fn test_chunked_proving_key_rejects_tampered_chunk() {
+        let (_proof, _c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let dir = std::env::temp_dir().join("poof_chunked_pk_tampered");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        save_proving_key_chunked(&pk, &dir, 512).expect("chunked save failed");
+        let first = dir.join(chunk_file_name(0));
+        let mut bytes = std::fs::read(&first).unwrap();
+        bytes[0] ^= 0xff;
+        std::fs::write(&first, bytes).unwrap();
+
+        let err = load_proving_key_chunked(&dir).expect_err("tampered key should be rejected");
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_chunked_proving_key_rejects_manifest_sizes_without_allocating_them() {
+        let (_proof, _c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let dir = std::env::temp_dir().join("poof_chunked_pk_bad_size");
+        let _ = std::fs::remove_dir_all(&dir);
+        save_proving_key_chunked(&pk, &dir, 512).expect("chunked save failed");
+        let manifest = std::fs::read_to_string(dir.join(MANIFEST_FILE)).unwrap();
+        let total = manifest.lines().find_map(|line| line.strip_prefix("total_bytes=")).unwrap().to_string();
+
+        for size in [usize::MAX.to_string(), "100".to_string()] {
+            let tampered = manifest.replace(&format!("total_bytes={total}"), &format!("total_bytes={size}"));
+            std::fs::write(dir.join(MANIFEST_FILE), tampered).unwrap();
+            let err = load_proving_key_chunked(&dir).expect_err("wrong size should be rejected");
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        }
+    }
+
+    #[test]
+    fn test_versioned_artifacts_round_trip() {
+        let (proof, c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let dir = std::env::temp_dir().join("poof_versioned_round_trip");
+
+        let proof_path = save_proof(&proof, &dir).expect("saving proof failed");
+        let input_path = save_public_input(&c, &dir).expect("saving input failed");
+        let vk_path = save_verifying_key(&pk.vk, &dir).expect("saving vk failed");
+
+        assert_eq!(std::fs::read(&proof_path).unwrap()[0], ARTIFACT_VERSION);
+        assert_eq!(load_proof(&proof_path).expect("loading proof failed"), proof);
+        assert_eq!(load_public_input(&input_path).expect("loading input failed"), c);
+        assert_eq!(load_verifying_key(&vk_path).expect("loading vk failed"), pk.vk);
+    }
+
+    #[test]
+    fn test_save_proof_refuses_off_curve_points() {
+        let (mut proof, _c, _pk) = generate_proof(3, 4).expect("proof generation failed");
+        proof.a = ark_bn254::G1Affine::new_unchecked(proof.a.x, proof.a.y + ark_bn254::Fq::from(1u64));
+        let dir = std::env::temp_dir().join("poof_off_curve_proof");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let err = save_proof(&proof, &dir).expect_err("off-curve proof should be refused");
+        assert!(matches!(err, ProverError::InvalidPoint(_)));
+        assert!(!dir.join(PROOFS_DIR).join("proof.bin").exists());
+
+        let path = save_proof_with_validation(&proof, &dir, false).expect("unvalidated save failed");
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn test_dry_run_writer_creates_nothing() {
+        let (proof, c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let dir = std::env::temp_dir().join("poof_dry_run_writer");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let dirs = ArtifactDirs::under(&dir);
+        let proof_path = save_proof_with_writer(&proof, &dirs, Writer::DryRun).expect("dry-run proof failed");
+        let vk_path = save_verifying_key_with_writer(&pk.vk, &dirs, Writer::DryRun).expect("dry-run vk failed");
+        save_calldata_with_writer(&proof, &c, &dir.join("calldata.bin"), CalldataMode::Pvm, Writer::DryRun)
+            .expect("dry-run calldata failed");
+
+        assert_eq!(proof_path, dir.join(PROOFS_DIR).join("proof.bin"));
+        assert_eq!(vk_path, dir.join(KEYS_DIR).join("verifying_key.bin"));
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn test_multi_proof_file_round_trip_and_count_check() {
+        let (_proof, _c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let proofs: Vec<_> = [(3, 4), (5, 6), (7, 8)]
+            .into_iter()
+            .map(|(a, b)| crate::prove_with_key(&pk, a, b, false).expect("proof generation failed").0)
+            .collect();
+        let path = std::env::temp_dir().join("poof_multi_proof.bin");
+
+        save_proofs(&proofs, &path).expect("saving proofs failed");
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), 1 + 8 + 3 * 128);
+        assert_eq!(load_proofs(&path).expect("loading proofs failed"), proofs);
+
+        save_proofs(&[], &path).expect("saving no proofs failed");
+        assert!(load_proofs(&path).expect("loading no proofs failed").is_empty());
+
+        // Count bytes follow the version byte
+        save_proofs(&proofs, &path).unwrap();
+        let original = std::fs::read(&path).unwrap();
+        for count in [2u64, 4, u64::MAX] {
+            let mut corrupted = original.clone();
+            corrupted[1..9].copy_from_slice(&count.to_le_bytes());
+            std::fs::write(&path, &corrupted).unwrap();
+            let err = load_proofs(&path).expect_err("a wrong count must be refused");
+            assert!(matches!(err, ProverError::WrongArtifactType { artifact: "proof list", .. }), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_loaders_refuse_files_of_another_artifact_type() {
+        let (proof, c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let dir = std::env::temp_dir().join("poof_wrong_artifact_type");
+        let proof_path = save_proof(&proof, &dir).expect("saving proof failed");
+        let input_path = save_public_input(&c, &dir).expect("saving input failed");
+        let vk_path = save_verifying_key(&pk.vk, &dir).expect("saving vk failed");
+
+        match crate::load_verifying_key_from_file(proof_path.to_str().unwrap()) {
+            Err(ProverError::WrongArtifactType { artifact, actual_size, .. }) => {
+                assert_eq!((artifact, actual_size), ("verifying key", 130));
+            }
+            other => panic!("expected WrongArtifactType, got {:?}", other.map(|_| ())),
+        }
+        assert!(matches!(load_proof(&vk_path), Err(ProverError::WrongArtifactType { artifact: "proof", .. })));
+        assert!(matches!(
+            load_public_input(&proof_path),
+            Err(ProverError::WrongArtifactType { artifact: "public input", .. })
+        ));
+        assert!(matches!(load_verifying_key(&input_path), Err(ProverError::WrongArtifactType { .. })));
+    }
+
+    #[test]
+    fn test_proof_encoding_is_detected() {
+        let (proof, _c, _pk) = generate_proof(3, 4).expect("proof generation failed");
+        for (encoding, len) in [(ProofEncoding::Compressed, 128), (ProofEncoding::Uncompressed, 256)] {
+            let bytes = encode_proof(&proof, encoding);
+            assert_eq!(bytes.len(), len);
+            assert_eq!(decode_proof(&bytes).expect("decoding failed"), (proof.clone(), encoding));
+        }
+        assert!(decode_proof(&[0u8; 100]).is_err());
+    }
+
+    #[test]
+    fn test_bundle_round_trip_verifies() {
+        let (proof, c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let bundle = ProofBundle::new(proof, c, pk.vk);
+        let dir = std::env::temp_dir().join("poof_bundle_round_trip");
+
+        let path = save_bundle(&bundle, &dir).expect("saving bundle failed");
+        let loaded = load_bundle(&path).expect("loading bundle failed");
+        assert_eq!(loaded, bundle);
+        assert!(loaded.verify().expect("verification failed"));
+    }
+
+    #[test]
+    fn test_proof_from_another_arkworks_format_is_rejected() {
+        let (proof, _c, _pk) = generate_proof(3, 4).expect("proof generation failed");
+        let dir = std::env::temp_dir().join("poof_proof_format_tag");
+
+        let proof_path = save_proof(&proof, &dir).expect("saving proof failed");
+        let mut bytes = std::fs::read(&proof_path).unwrap();
+        assert_eq!(bytes[1..3], [CURVE_BN254, ARK_SERIALIZE_FORMAT]);
+        bytes[2] = ARK_SERIALIZE_FORMAT + 1;
+        std::fs::write(&proof_path, &bytes).unwrap();
+
+        match load_proof(&proof_path) {
+            Err(ProverError::IncompatibleFormat { tag, found, expected }) => {
+                assert_eq!(tag, "serialization format");
+                assert_eq!((found, expected), (ARK_SERIALIZE_FORMAT + 1, ARK_SERIALIZE_FORMAT));
+            }
+            other => panic!("expected IncompatibleFormat, got {:?}", other),
+        }
+
+        bytes[1] = CURVE_BN254 + 1;
+        assert!(matches!(unframe_proof(&bytes[1..]), Err(ProverError::IncompatibleFormat { tag: "curve", .. })));
+    }
+
+    #[test]
+    fn test_version_1_proof_file_still_loads() {
+        let (proof, _c, _pk) = generate_proof(3, 4).expect("proof generation failed");
+        let path = std::env::temp_dir().join("poof_version_1_proof.bin");
+
+        // Version 1 layout: the version byte, then the bare compressed proof
+        let mut bytes = vec![1u8];
+        bytes.extend_from_slice(&encode_proof(&proof, ProofEncoding::Compressed));
+        std::fs::write(&path, &bytes).unwrap();
+
+        assert_eq!(load_proof(&path).expect("version 1 proof should load"), proof);
+        assert_eq!(read_versioned_proof(&path).unwrap(), frame_proof(&bytes[1..]));
+    }
+
+    #[test]
+    fn test_bumped_version_is_rejected() {
+        let (_proof, _c, pk) = generate_proof(3, 4).expect("proof generation failed");
+        let dir = std::env::temp_dir().join("poof_versioned_bumped");
+
+        let vk_path = save_verifying_key(&pk.vk, &dir).expect("saving vk failed");
+        let mut bytes = std::fs::read(&vk_path).unwrap();
+        bytes[0] = ARTIFACT_VERSION + 1;
+        std::fs::write(&vk_path, bytes).unwrap();
+
+        match load_verifying_key(&vk_path) {
+            Err(ProverError::UnsupportedVersion { found, expected }) => {
+                assert_eq!((found, expected), (ARTIFACT_VERSION + 1, ARTIFACT_VERSION));
+            }
+            other => panic!("expected UnsupportedVersion, got {:?}", other.map(|_| ())),
+        }
+    }
+}
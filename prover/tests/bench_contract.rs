@@ -0,0 +1,19 @@
+// Runs the `bench_contract` example's core function as an integration test.
+
+#[path = "../examples/bench_contract.rs"]
+#[allow(dead_code)]
+mod example;
+
+#[test]
+fn every_call_fits_the_contract_heap() {
+    let costs = example::run(5).expect("benchmark failed");
+    assert_eq!(costs.len(), 5);
+
+    for cost in &costs {
+        assert!(cost.valid);
+        assert!(cost.allocations > 0);
+        assert!(cost.heap_bytes <= example::HEAP_SIZE, "{} bytes overflow the arena", cost.heap_bytes);
+    }
+    // Nothing carries over from one call to the next
+    assert!(costs.iter().all(|cost| cost.heap_bytes == costs[0].heap_bytes));
+}
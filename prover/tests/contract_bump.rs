@@ -0,0 +1,50 @@
+// Host-side tests of the PVM contract's bump allocator.
+// `bump.rs` is compiled here exactly as the contract includes it.
+
+#[path = "../../verifier-contract/src/bump.rs"]
+mod contract;
+
+use contract::{BumpArena, bump};
+use std::alloc::{GlobalAlloc, Layout};
+
+fn alloc(arena: &BumpArena<256>, size: usize, align: usize) -> *mut u8 {
+    unsafe { arena.alloc(Layout::from_size_align(size, align).unwrap()) }
+}
+
+#[test]
+fn odd_sizes_are_followed_by_aligned_blocks() {
+    let arena = BumpArena::<256>::new();
+    for size in [1, 3, 7] {
+        assert!(!alloc(&arena, size, 1).is_null());
+    }
+    for align in [2, 4, 8, 16] {
+        let ptr = alloc(&arena, 5, align);
+        assert!(!ptr.is_null());
+        assert_eq!(ptr as usize % align, 0, "misaligned for align {}", align);
+    }
+}
+
+#[test]
+fn oversized_layouts_return_null_without_consuming_the_arena() {
+    let arena = BumpArena::<256>::new();
+    assert!(!alloc(&arena, 8, 8).is_null());
+    let used = arena.used();
+
+    assert!(alloc(&arena, 257, 1).is_null());
+    assert!(alloc(&arena, isize::MAX as usize - 4096, 1).is_null());
+    assert!(alloc(&arena, 1, 1 << 20).is_null());
+    assert_eq!(arena.used(), used);
+
+    assert!(!alloc(&arena, 256 - used, 1).is_null());
+    assert!(alloc(&arena, 1, 1).is_null());
+}
+
+#[test]
+fn bump_rejects_bad_alignment_and_overflow() {
+    assert_eq!(bump(0x1001, 0, 4, 4, 64), Some(3));
+    assert_eq!(bump(0x1000, 0, 4, 3, 64), None);
+    assert_eq!(bump(0x1000, 0, 4, 0, 64), None);
+    assert_eq!(bump(usize::MAX - 2, 0, 1, 8, usize::MAX), None);
+    assert_eq!(bump(0x1000, usize::MAX, 1, 1, usize::MAX), None);
+    assert_eq!(bump(0x1000, 8, usize::MAX, 1, usize::MAX), None);
+}
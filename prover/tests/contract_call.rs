@@ -0,0 +1,91 @@
+// Host-side runs of the PVM contract's `call()` entry point.
+// `entry.rs` and `verify.rs` are compiled here exactly as the contract includes them,
+// with the `uapi` host functions replaced by in-memory calldata and a captured return value.
+
+#[path = "../../verifier-contract/src/entry.rs"]
+mod entry;
+#[path = "../../verifier-contract/src/verify.rs"]
+mod verify;
+
+use ark_bn254::{Bn254, Fr};
+use ark_groth16::VerifyingKey;
+use ark_serialize::CanonicalSerialize;
+use entry::{Host, handle_call};
+use prover::{build_pvm_calldata, generate_proof};
+use verify::VerifyOptions;
+
+/// Serves `calldata` to the contract and records what it returns
+struct MemoryHost {
+    calldata: Vec<u8>,
+    returned: Option<Vec<u8>>,
+}
+
+impl Host for MemoryHost {
+    fn call_data_copy(&mut self, out: &mut [u8], offset: u32) {
+        out.fill(0);
+        let available = self.calldata.get(offset as usize..).unwrap_or(&[]);
+        let len = available.len().min(out.len());
+        out[..len].copy_from_slice(&available[..len]);
+    }
+
+    fn return_value(&mut self, data: &[u8]) {
+        assert!(self.returned.is_none(), "contract returned twice");
+        self.returned = Some(data.to_vec());
+    }
+}
+
+/// Run `call()` on `calldata` and decode the returned Solidity bool
+fn call(vk: &VerifyingKey<Bn254>, calldata: &[u8]) -> bool {
+    let mut vk_bytes = Vec::new();
+    vk.serialize_uncompressed(&mut vk_bytes).unwrap();
+
+    let mut host = MemoryHost { calldata: calldata.to_vec(), returned: None };
+    handle_call(&mut host, &vk_bytes, VerifyOptions::default());
+
+    let word = host.returned.expect("contract did not return");
+    assert_eq!(word.len(), 32);
+    assert!(word[..31].iter().all(|&b| b == 0), "not an ABI-encoded bool");
+    match word[31] {
+        0 => false,
+        1 => true,
+        other => panic!("not an ABI-encoded bool: {}", other),
+    }
+}
+
+#[test]
+fn valid_calldata_returns_true() {
+    let (proof, c, pk) = generate_proof(3, 4).expect("proof generation failed");
+    assert!(call(&pk.vk, &build_pvm_calldata(&proof, &c)));
+}
+
+#[test]
+fn wrong_input_or_key_returns_false() {
+    let (proof, _c, pk) = generate_proof(3, 4).expect("proof generation failed");
+    assert!(!call(&pk.vk, &build_pvm_calldata(&proof, &Fr::from(13u64))));
+
+    let (_proof, c, other) = generate_proof(3, 4).expect("proof generation failed");
+    assert!(!call(&other.vk, &build_pvm_calldata(&proof, &c)));
+}
+
+#[test]
+fn calldata_for_another_function_returns_false() {
+    let (proof, c, pk) = generate_proof(3, 4).expect("proof generation failed");
+    let mut calldata = build_pvm_calldata(&proof, &c);
+    assert_eq!(calldata[..4], verify::SELECTOR);
+
+    // A valid proof behind a different selector is still refused
+    calldata[..4].copy_from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+    assert!(!call(&pk.vk, &calldata));
+}
+
+#[test]
+fn malformed_calldata_returns_false() {
+    let (proof, c, pk) = generate_proof(3, 4).expect("proof generation failed");
+    let calldata = build_pvm_calldata(&proof, &c);
+
+    // Truncated calldata reaches the contract zero-padded
+    assert!(!call(&pk.vk, &calldata[..100]));
+    assert!(!call(&pk.vk, &[]));
+    // Bytes that do not decode to a point
+    assert!(!call(&pk.vk, &[0xff; 164]));
+}
@@ -0,0 +1,114 @@
+// Host-side tests of the PVM contract's verification logic.
+// `verify.rs` is compiled here exactly as the contract includes it.
+
+#[path = "../../verifier-contract/src/verify.rs"]
+mod contract;
+
+use ark_bn254::{Bn254, Fq2, Fr, G2Affine};
+use ark_ff::UniformRand;
+use ark_groth16::{Groth16, VerifyingKey};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_snark::SNARK;
+use contract::{CALLDATA_LEN, VerifyOptions, run_verify};
+use prover::circuit::MulCircuit;
+use prover::utils::{CalldataMode, export_verifying_key_to_rs, save_calldata};
+use prover::{build_pvm_calldata, generate_proof, parse_verifying_key_rs_source, verify_pvm_calldata};
+
+const REJECT_ZERO: VerifyOptions = VerifyOptions { reject_zero_input: true };
+
+fn vk_bytes(vk: &VerifyingKey<Bn254>) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    vk.serialize_uncompressed(&mut bytes).unwrap();
+    bytes
+}
+
+#[test]
+fn valid_calldata_verifies_like_the_off_chain_mirror() {
+    let (proof, c, pk) = generate_proof(3, 4).expect("proof generation failed");
+    let calldata = build_pvm_calldata(&proof, &c);
+
+    assert!(run_verify(&vk_bytes(&pk.vk), &calldata, VerifyOptions::default()));
+    assert!(run_verify(&vk_bytes(&pk.vk), &calldata, REJECT_ZERO));
+    assert!(verify_pvm_calldata(&calldata, &pk.vk));
+}
+
+#[test]
+fn contract_expects_the_selector_the_prover_writes() {
+    assert_eq!(contract::SELECTOR, prover::utils::CALLDATA_SELECTOR);
+}
+
+#[test]
+fn solidity_fixture_calldata_verifies() {
+    let (proof, c, pk) = generate_proof(3, 4).expect("proof generation failed");
+    let source = prover::solidity::solidity_test_source(&proof, &c).unwrap();
+
+    let start = source.find("hex\"").expect("fixture embeds no calldata") + 4;
+    let digits = &source[start..start + source[start..].find('"').unwrap()];
+    let calldata: Vec<u8> =
+        (0..digits.len()).step_by(2).map(|i| u8::from_str_radix(&digits[i..i + 2], 16).unwrap()).collect();
+
+    let calldata: [u8; CALLDATA_LEN] = calldata.try_into().expect("fixture calldata is not PVM calldata");
+    assert!(run_verify(&vk_bytes(&pk.vk), &calldata, VerifyOptions::default()));
+}
+
+#[test]
+fn zero_input_is_rejected_only_under_the_feature() {
+    // 0 * 4 = 0 is a perfectly valid proof of a trivial statement
+    let (proof, c, pk) = generate_proof(0, 4).expect("proof generation failed");
+    let calldata = build_pvm_calldata(&proof, &c);
+    let vk = vk_bytes(&pk.vk);
+
+    assert!(run_verify(&vk, &calldata, VerifyOptions::default()));
+    assert!(!run_verify(&vk, &calldata, REJECT_ZERO));
+
+    // An unrelated proof paired with a zero input is rejected either way
+    let (other, _c, _pk) = generate_proof(3, 4).expect("proof generation failed");
+    let calldata = build_pvm_calldata(&other, &Fr::from(0u64));
+    assert!(!run_verify(&vk, &calldata, VerifyOptions::default()));
+    assert!(!run_verify(&vk, &calldata, REJECT_ZERO));
+}
+
+#[test]
+fn exported_key_file_verifies_saved_calldata() {
+    // The deploy pipeline: setup, export the key the contract `include!`s, save calldata
+    let mut rng = rand::thread_rng();
+    let (pk, _vk) = Groth16::<Bn254>::circuit_specific_setup(MulCircuit::setup_shape(), &mut rng).unwrap();
+    let proof = Groth16::<Bn254>::prove(&pk, MulCircuit::instance(3, 4), &mut rng).unwrap();
+
+    let out_dir = std::env::temp_dir().join("contract_verify_pipeline");
+    let key_file = export_verifying_key_to_rs(&pk.vk, &out_dir).unwrap();
+    let calldata_file = out_dir.join("calldata.bin");
+    save_calldata(&proof, &Fr::from(12u64), &calldata_file, CalldataMode::Pvm).unwrap();
+
+    let embedded = parse_verifying_key_rs_source(&std::fs::read_to_string(key_file).unwrap()).unwrap();
+    let calldata: [u8; CALLDATA_LEN] = std::fs::read(&calldata_file).unwrap().try_into().unwrap();
+    assert!(run_verify(&embedded, &calldata, VerifyOptions::default()));
+
+    let wrong_input = build_pvm_calldata(&proof, &Fr::from(13u64));
+    assert!(!run_verify(&embedded, &wrong_input, VerifyOptions::default()));
+}
+
+#[test]
+fn proof_with_b_outside_the_g2_subgroup_is_rejected() {
+    let (proof, c, pk) = generate_proof(3, 4).expect("proof generation failed");
+    let vk = vk_bytes(&pk.vk);
+
+    // A point on the twist but outside the prime-order subgroup (G2's cofactor is large)
+    let mut rng = rand::thread_rng();
+    let off_subgroup = loop {
+        let candidate = G2Affine::get_point_from_x_unchecked(Fq2::rand(&mut rng), true);
+        if let Some(point) = candidate.filter(|p| !p.is_in_correct_subgroup_assuming_on_curve()) {
+            break point;
+        }
+    };
+    let mut b_bytes = Vec::new();
+    off_subgroup.serialize_compressed(&mut b_bytes).unwrap();
+
+    let mut calldata = build_pvm_calldata(&proof, &c);
+    calldata[4 + 32..4 + 96].copy_from_slice(&b_bytes);
+
+    // The bytes are a well-formed compressed point; only the subgroup check refuses them
+    let decoded = ark_groth16::Proof::<Bn254>::deserialize_compressed_unchecked(&calldata[4..132]).unwrap();
+    assert!(decoded.b.is_on_curve());
+    assert!(!run_verify(&vk, &calldata, VerifyOptions::default()));
+}
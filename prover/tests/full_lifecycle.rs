@@ -0,0 +1,14 @@
+// Runs the `full_lifecycle` example's core function as an integration test.
+
+#[path = "../examples/full_lifecycle.rs"]
+#[allow(dead_code)]
+mod example;
+
+#[test]
+fn full_lifecycle_example_verifies() {
+    let out_dir = std::env::temp_dir().join("poof_full_lifecycle_test");
+    let _ = std::fs::remove_dir_all(&out_dir);
+
+    assert!(example::run(&out_dir).expect("lifecycle failed"));
+    assert!(out_dir.join("Verifier.t.sol").exists());
+}
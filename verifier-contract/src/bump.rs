@@ -0,0 +1,67 @@
+// Bump allocator backing the contract's heap, free of PolkaVM host functions.
+// Like `verify.rs`, the prover's test suite compiles this file on the host
+// (via `#[path]`) to check allocation behaviour on pathological layouts.
+
+use core::alloc::{GlobalAlloc, Layout};
+use core::cell::{Cell, UnsafeCell};
+use core::ptr::null_mut;
+
+/// Fixed-capacity bump allocator over an inline `N`-byte arena. Memory is never freed.
+pub struct BumpArena<const N: usize> {
+    heap: UnsafeCell<[u8; N]>,
+    offset: Cell<usize>,
+}
+
+// PolkaVM contracts run single-threaded
+unsafe impl<const N: usize> Sync for BumpArena<N> {}
+
+impl<const N: usize> BumpArena<N> {
+    pub const fn new() -> Self {
+        Self { heap: UnsafeCell::new([0; N]), offset: Cell::new(0) }
+    }
+
+    /// Bytes handed out so far, alignment padding included
+    pub fn used(&self) -> usize {
+        self.offset.get()
+    }
+}
+
+impl<const N: usize> Default for BumpArena<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Offset into an arena starting at address `base`, with `offset` bytes already used,
+/// where a `size`-byte block aligned to `align` can start. `None` if `align` is not a
+/// power of two, the block does not fit in `capacity`, or any step overflows `usize`.
+/// Alignment is applied to the address, not the offset, so it holds whatever the arena's own alignment.
+pub fn bump(base: usize, offset: usize, size: usize, align: usize, capacity: usize) -> Option<usize> {
+    if !align.is_power_of_two() {
+        return None;
+    }
+    let addr = base.checked_add(offset)?;
+    let aligned = addr.checked_add(align - 1)? & !(align - 1);
+    let start = aligned - base;
+    let end = start.checked_add(size)?;
+    if end > capacity {
+        return None;
+    }
+    Some(start)
+}
+
+unsafe impl<const N: usize> GlobalAlloc for BumpArena<N> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let base = self.heap.get() as *mut u8;
+        match bump(base as usize, self.offset.get(), layout.size(), layout.align(), N) {
+            Some(start) => {
+                self.offset.set(start + layout.size());
+                // `bump` keeps start + size within the N-byte arena
+                unsafe { base.add(start) }
+            }
+            None => null_mut(), // triggers trap → ContractTrapped
+        }
+    }
+
+    unsafe fn dealloc(&self, _ptr: *mut u8, _layout: Layout) { /* no-op */ }
+}
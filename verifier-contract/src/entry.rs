@@ -0,0 +1,29 @@
+// Body of the contract's `call()` entry point, written against a `Host` trait
+// instead of the `uapi` host functions. `main.rs` implements `Host` for the PolkaVM
+// runtime; the prover's test suite compiles this file (and `verify.rs`) on the host
+// with an in-memory `Host`, so `call()` runs end to end without a deployment.
+
+use crate::verify::{self, VerifyOptions, CALLDATA_LEN};
+
+/// The host functions `call()` uses
+pub trait Host {
+    /// Copy call data from `offset` into `out`, zero-filling past the end of the call data
+    fn call_data_copy(&mut self, out: &mut [u8], offset: u32);
+    /// Hand `data` back to the caller; on PolkaVM this ends execution
+    fn return_value(&mut self, data: &[u8]);
+}
+
+/// Read the calldata, verify it against `vk_bytes` and return the result as a Solidity bool
+pub fn handle_call<H: Host>(host: &mut H, vk_bytes: &[u8], options: VerifyOptions) {
+    let mut calldata = [0u8; CALLDATA_LEN];
+    host.call_data_copy(&mut calldata, 0);
+
+    return_bool(host, verify::run_verify(vk_bytes, &calldata, options));
+}
+
+/// Solidity-style bool: a 32-byte word holding 0 or 1
+pub fn return_bool<H: Host>(host: &mut H, b: bool) {
+    let mut out = [0u8; 32];
+    out[31] = b as u8;
+    host.return_value(&out);
+}
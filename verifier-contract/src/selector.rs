@@ -0,0 +1,124 @@
+// Keccak-256 as a `const fn`, so function selectors are derived from their Solidity
+// signature at compile time instead of being pasted in as byte literals.
+// `verify.rs` includes this file for the contract's `SELECTOR`, and the prover
+// includes it (via `#[path]`) for `utils::CALLDATA_SELECTOR`, so both sides compute
+// the selector the same way. No dependencies, so it builds for PolkaVM and the host.
+
+/// Bytes absorbed per permutation: 1600 - 2 * 256 bits
+const RATE: usize = 136;
+
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000_0000_0000_0001, 0x0000_0000_0000_8082, 0x8000_0000_0000_808a, 0x8000_0000_8000_8000,
+    0x0000_0000_0000_808b, 0x0000_0000_8000_0001, 0x8000_0000_8000_8081, 0x8000_0000_0000_8009,
+    0x0000_0000_0000_008a, 0x0000_0000_0000_0088, 0x0000_0000_8000_8009, 0x0000_0000_8000_000a,
+    0x0000_0000_8000_808b, 0x8000_0000_0000_008b, 0x8000_0000_0000_8089, 0x8000_0000_0000_8003,
+    0x8000_0000_0000_8002, 0x8000_0000_0000_0080, 0x0000_0000_0000_800a, 0x8000_0000_8000_000a,
+    0x8000_0000_8000_8081, 0x8000_0000_0000_8080, 0x0000_0000_8000_0001, 0x8000_0000_8000_8008,
+];
+
+/// Rotation of lane (x, y), at index x + 5 * y
+const ROTATIONS: [u32; 25] = [
+    0, 1, 62, 28, 27, //
+    36, 44, 6, 55, 20, //
+    3, 10, 43, 25, 39, //
+    41, 45, 15, 21, 8, //
+    18, 2, 61, 56, 14,
+];
+
+const fn keccak_f(mut a: [u64; 25]) -> [u64; 25] {
+    let mut round = 0;
+    while round < 24 {
+        // θ: xor every lane with the parities of two neighbouring columns
+        let mut parity = [0u64; 5];
+        let mut x = 0;
+        while x < 5 {
+            parity[x] = a[x] ^ a[x + 5] ^ a[x + 10] ^ a[x + 15] ^ a[x + 20];
+            x += 1;
+        }
+        let mut x = 0;
+        while x < 5 {
+            let d = parity[(x + 4) % 5] ^ parity[(x + 1) % 5].rotate_left(1);
+            let mut y = 0;
+            while y < 25 {
+                a[x + y] ^= d;
+                y += 5;
+            }
+            x += 1;
+        }
+
+        // ρ and π: lane (x, y) is rotated and moved to (y, 2x + 3y)
+        let mut b = [0u64; 25];
+        let mut i = 0;
+        while i < 25 {
+            let (x, y) = (i % 5, i / 5);
+            b[y + 5 * ((2 * x + 3 * y) % 5)] = a[i].rotate_left(ROTATIONS[i]);
+            i += 1;
+        }
+
+        // χ
+        let mut i = 0;
+        while i < 25 {
+            let (x, row) = (i % 5, i - i % 5);
+            a[i] = b[i] ^ (!b[row + (x + 1) % 5] & b[row + (x + 2) % 5]);
+            i += 1;
+        }
+
+        // ι
+        a[0] ^= ROUND_CONSTANTS[round];
+        round += 1;
+    }
+    a
+}
+
+const fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut state = [0u64; 25];
+    let mut offset = 0;
+    loop {
+        // The final block holds what is left (possibly nothing) plus the padding
+        let mut block = [0u8; RATE];
+        let remaining = data.len() - offset;
+        let last = remaining < RATE;
+        let mut i = 0;
+        while i < RATE && i < remaining {
+            block[i] = data[offset + i];
+            i += 1;
+        }
+        if last {
+            block[remaining] ^= 0x01;
+            block[RATE - 1] ^= 0x80;
+        }
+
+        let mut lane = 0;
+        while lane < RATE / 8 {
+            let mut bytes = [0u8; 8];
+            let mut i = 0;
+            while i < 8 {
+                bytes[i] = block[8 * lane + i];
+                i += 1;
+            }
+            state[lane] ^= u64::from_le_bytes(bytes);
+            lane += 1;
+        }
+        state = keccak_f(state);
+
+        if last {
+            break;
+        }
+        offset += RATE;
+    }
+
+    let mut digest = [0u8; 32];
+    let mut i = 0;
+    while i < 32 {
+        digest[i] = state[i / 8].to_le_bytes()[i % 8];
+        i += 1;
+    }
+    digest
+}
+
+/// Solidity function selector: the first 4 bytes of `keccak256(signature)`,
+/// e.g. `function_selector("verifyProofFromCalldata(bytes)")`
+pub const fn function_selector(signature: &str) -> [u8; 4] {
+    let digest = keccak256(signature.as_bytes());
+    [digest[0], digest[1], digest[2], digest[3]]
+}
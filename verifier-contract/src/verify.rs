@@ -0,0 +1,74 @@
+// Verification logic of the contract, free of PolkaVM host functions.
+// `call()` copies the calldata and hands it here; keeping this file host-agnostic
+// lets the prover's test suite compile it on the host (via `#[path]`) and check
+// the exact on-chain decoding without a PolkaVM runtime.
+
+use ark_bn254::{Bn254, Fr};
+use ark_ff::Zero;
+use ark_groth16::{prepare_verifying_key, Groth16, Proof, VerifyingKey};
+use ark_serialize::{CanonicalDeserialize, Compress, Validate};
+
+#[path = "selector.rs"]
+mod selector;
+
+// ┌──────────┬──────────────────────────┬───────────────────────┐
+// │ 0..3 sel │ 4..131 compressed Proof │ 132..163 public input │
+// └──────────┴──────────────────────────┴───────────────────────┘
+pub const CALLDATA_LEN: usize = 164;
+
+/// Function whose calldata the contract verifies
+pub const SIGNATURE: &str = "verifyProofFromCalldata(bytes)";
+
+/// Selector the calldata must start with: `keccak256(SIGNATURE)[..4]`, the prover's `utils::CALLDATA_SELECTOR`
+pub const SELECTOR: [u8; 4] = selector::function_selector(SIGNATURE);
+
+/// Compile-time policy of the contract
+#[derive(Clone, Copy, Default)]
+pub struct VerifyOptions {
+    /// Return `false` for a zero public input, which `a * b = 0` makes trivially provable
+    pub reject_zero_input: bool,
+}
+
+/// Decode the embedded VK and the calldata, then run the pairing check.
+/// Calldata meant for another function (a selector other than `SELECTOR`) and
+/// any decoding failure are reported as `false`.
+pub fn run_verify(vk_bytes: &[u8], calldata: &[u8; CALLDATA_LEN], options: VerifyOptions) -> bool {
+    if calldata[..4] != SELECTOR {
+        return false;
+    }
+    let proof_bytes = &calldata[4..132];
+    let input_bytes = &calldata[132..164];
+
+    // ----------  Deserialize verifying-key (once per call) ----------
+    let mut vk_src = vk_bytes;
+    let vk: VerifyingKey<Bn254> = match VerifyingKey::deserialize_with_mode(&mut vk_src, Compress::No, Validate::Yes) {
+        Ok(vk) => vk,
+        Err(_) => return false,
+    };
+
+    // ----------  Deserialize proof & public input  ----------
+    // Validate::Yes checks that A, B and C are on the curve and in the prime-order
+    // subgroup. G2 has a large cofactor, so an on-curve B outside the subgroup would
+    // otherwise reach the pairing and open the verifier to malleated proofs.
+    let proof: Proof<Bn254> = match Proof::deserialize_with_mode(proof_bytes, Compress::Yes, Validate::Yes) {
+        Ok(p) => p,
+        Err(_) => return false,
+    };
+
+    // Public input is a big-endian 32-byte word; arkworks expects little-endian
+    let mut input_le = [0u8; 32];
+    input_le.copy_from_slice(input_bytes);
+    input_le.reverse();
+    let public: Fr = match Fr::deserialize_compressed(&input_le[..]) {
+        Ok(f) => f,
+        Err(_) => return false,
+    };
+
+    if options.reject_zero_input && public.is_zero() {
+        return false;
+    }
+
+    // ----------  Verify  ----------
+    let pvk = prepare_verifying_key(&vk);
+    Groth16::<Bn254>::verify_proof(&pvk, &proof, &[public]).unwrap_or(false)
+}
@@ -18,7 +18,7 @@ echo "🔧 Building Rust verifier …"
 
 echo "🔐 Generating proof & calldata …"
 cargo run --release --bin "$PROVER_BIN" -- prove \
-      --a 3 --b 4 --c 12 --out "$CALDATA_FILE"
+      --a 3 --b 4 --c 12 --out "$CALDATA_FILE" --force
 
 echo "♻️  Estimating deploy gas …"
 DEPLOY_GAS=$(cast estimate \
@@ -1,15 +1,27 @@
-use ark_bn254::{Bn254, Fr};
-use ark_groth16::Groth16;
-use prover::circuit::MulCircuit;
-use prover::utils::{save_calldata, export_verifying_key_to_rs};
-use prover::utils::{save_proof, save_public_input, save_verifying_key};
+use ark_bn254::{Bn254, Fr, G1Affine, G2Affine};
+use ark_groth16::{Groth16, Proof};
+use ark_snark::SNARK;
+use prover::circuit::{Assignment, BitOutput, FactorCircuit, MulCircuit, ParityCircuit, RangeWithBitCircuit, SumCircuit};
+use ark_relations::r1cs::ConstraintSynthesizer;
+use prover::r1cs::{r1cs_export_with_assignment, R1csExport};
+use prover::calldata::{calldata_payload, extract_public_input_from_calldata, PROOF_LEN};
+use prover::summary::{serialized_sizes, ProofSummary};
+use prover::utils::hex_string;
+use prover::{parse_verifying_key_rs_source, vk_diff, vk_fingerprint};
+use prover::utils::{save_calldata_with_writer, export_verifying_key_to_rs_with_writer, CalldataMode, Writer};
+use prover::utils::{save_prepared_verifying_key_with_writer, save_proof_with_writer, save_public_input_with_writer};
+use prover::utils::{save_verifying_key_with_writer, ArtifactDirs};
+use prover::utils::{load_prepared_verifying_key, load_proof, load_public_input, load_verifying_key, read_versioned};
+use prover::utils::read_versioned_proof;
+use prover::utils::{decode_proof, encode_proof, save_proof_as_with_writer, unframe_proof, ProofEncoding};
+use prover::utils::{KEYS_DIR, PROOFS_DIR};
+use prover::{check_satisfied, parse_fr, prepare_and_verify, prove_circuit, prove_hash_preimage, verify_with_prepared, ProverError};
 
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use rand::thread_rng;
-use ark_groth16::{Proof, VerifyingKey, prepare_verifying_key};
-use ark_serialize::CanonicalDeserialize;
-use std::fs::File;
-use std::io::BufReader;
+use ark_groth16::{prepare_verifying_key, VerifyingKey};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use std::io::Read;
 use std::path::PathBuf;
 use std::path::Path;
 use anyhow::{Result, Context};  
@@ -20,22 +32,77 @@ use anyhow::{Result, Context};
 #[command(name = "zkcli")]
 #[command(about = "Generate zkSNARK proof and calldata")]
 struct Cli {
+    /// Directory artefacts are written to, under `keys/` and `proofs/`
+    #[arg(long, global = true, default_value = ".")]
+    output_dir: PathBuf,
+
+    /// Directory for verifying keys, e.g. a secure store [default: <output-dir>/keys]
+    #[arg(long, global = true)]
+    keys_dir: Option<PathBuf>,
+
+    /// Directory for proofs and public inputs [default: <output-dir>/proofs]
+    #[arg(long, global = true)]
+    proofs_dir: Option<PathBuf>,
+
+    /// Print the artefacts a command would write, with their sizes, without creating any files
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    /// Let a proving command replace an existing verifying_key.bin. Proofs and contracts
+    /// made with the old key stop verifying.
+    #[arg(long, global = true)]
+    force: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
 
+impl Cli {
+    /// Where keys and proofs go: `--keys-dir` / `--proofs-dir`, else under `--output-dir`
+    fn artifact_dirs(&self) -> ArtifactDirs {
+        ArtifactDirs {
+            keys: self.keys_dir.clone().unwrap_or_else(|| self.output_dir.join(KEYS_DIR)),
+            proofs: self.proofs_dir.clone().unwrap_or_else(|| self.output_dir.join(PROOFS_DIR)),
+        }
+    }
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Generate proof and calldata for a * b = c
     Prove {
-        #[arg(long)]
+        /// Falls back to ZKCLI_A so the secret stays out of the argument list
+        #[arg(long, env = "ZKCLI_A", hide_env_values = true)]
         a: u64,
-        #[arg(long)]
+        /// Falls back to ZKCLI_B so the secret stays out of the argument list
+        #[arg(long, env = "ZKCLI_B", hide_env_values = true)]
         b: u64,
         #[arg(long)]
         c: u64,
-        #[arg(long, default_value = "../calldata.bin")]
-        out: String,
+        /// Calldata file, relative to --output-dir
+        #[arg(long, default_value = "calldata.bin")]
+        out: PathBuf,
+        /// Proof file encoding: versioned binary, 0x-hex text, or snarkjs-style JSON
+        #[arg(long, value_enum, default_value_t = ProofFormat::Bin)]
+        format: ProofFormat,
+    },
+
+    /// Prove knowledge of a secret whose Poseidon hash becomes the public input.
+    /// The secret is read from stdin (or --secret-file), never from the argument list.
+    ProveHash {
+        /// File holding the secret as decimal or 0x-hex; stdin when omitted
+        #[arg(long)]
+        secret_file: Option<PathBuf>,
+    },
+
+    /// Prove a registered circuit (mul, parity or range) from a JSON witness of named assignments,
+    /// e.g. `{"a": 3, "b": 4}` for mul
+    ProveJson {
+        #[arg(long)]
+        witness: String,
+
+        #[arg(long, default_value = "mul")]
+        circuit: String,
     },
 
     /// Verify proof + public input using verifying key
@@ -46,106 +113,1254 @@ enum Commands {
         #[arg(long)]
         input: String,
 
+        /// Verifying key, prepared on every run
+        #[arg(long, required_unless_present = "pvk", conflicts_with = "pvk")]
+        vk: Option<String>,
+
+        /// Prepared verifying key written by `prove`; skips preparation
+        #[arg(long)]
+        pvk: Option<String>,
+
+        /// Print the result as one JSON object on stdout, for CI
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Verify every `<name>.proof.bin` / `<name>.input.bin` pair in a directory
+    VerifyDir {
+        #[arg(long)]
+        dir: String,
+
         #[arg(long)]
         vk: String,
+    },
+
+    /// Write the R1CS matrices and assignments of a sample circuit (mul, sum or factor) as JSON
+    DumpR1cs {
+        #[arg(long)]
+        circuit: String,
+        /// JSON file, relative to --output-dir
+        #[arg(long, default_value = "r1cs.json")]
+        out: PathBuf,
+    },
+
+    /// Report whether two verifying key files hold the same key, and which components differ
+    CompareVk {
+        #[arg(long)]
+        a: String,
+
+        #[arg(long)]
+        b: String,
+    },
+
+    /// Re-encode a proof file as compressed or uncompressed, detecting its current encoding
+    ConvertProof {
+        #[arg(long)]
+        input: String,
+
+        #[arg(long)]
+        output: String,
+
+        #[arg(long, value_enum)]
+        to: EncodingArg,
+    },
+
+    /// Rebuild PVM calldata from a saved proof and public input, without re-proving
+    MakeCalldata {
+        #[arg(long)]
+        proof: String,
+
+        #[arg(long)]
+        input: String,
+
+        #[arg(long)]
+        out: String,
+    },
+
+    /// Print the public input and proof points a calldata file encodes
+    InspectCalldata {
+        #[arg(long)]
+        path: String,
+    },
+
+    /// Print the fingerprint of the key embedded in a verifying_key_bytes.rs file
+    InspectKeyFile {
+        #[arg(long)]
+        path: String,
+    },
+
+    /// Summarize a saved verifying key: fingerprint, public inputs, size and curve
+    KeysInfo {
+        #[arg(long)]
+        vk: String,
+    },
+
+    /// Print the serialized sizes of a mul proof, verifying key and public input
+    Sizes,
+}
+
+/// `--to` of `ConvertProof`
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum EncodingArg {
+    Compressed,
+    Uncompressed,
+}
+
+impl From<EncodingArg> for ProofEncoding {
+    fn from(arg: EncodingArg) -> Self {
+        match arg {
+            EncodingArg::Compressed => ProofEncoding::Compressed,
+            EncodingArg::Uncompressed => ProofEncoding::Uncompressed,
+        }
+    }
+}
+
+/// `--format` of `Prove`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ProofFormat {
+    /// Versioned compressed proof, as written by `save_proof` (proofs/proof.bin)
+    Bin,
+    /// The compressed proof as `0x`-prefixed hex text (proofs/proof.hex)
+    Hex,
+    /// snarkjs' proof.json layout with decimal coordinates (proofs/proof.json)
+    Json,
+}
+
+/// Write `proof` to `dirs.proofs` in `format`
+fn write_proof(proof: &Proof<Bn254>, dirs: &ArtifactDirs, format: ProofFormat, writer: Writer) -> Result<PathBuf> {
+    if format == ProofFormat::Bin {
+        return Ok(save_proof_with_writer(proof, dirs, writer)?);
+    }
+    let (path, text) = match format {
+        ProofFormat::Hex => {
+            let bytes = encode_proof(proof, ProofEncoding::Compressed);
+            (dirs.proofs.join("proof.hex"), format!("0x{}\n", hex_string(&bytes)))
+        }
+        _ => (dirs.proofs.join("proof.json"), serde_json::to_string_pretty(&snarkjs_proof_json(proof))?),
+    };
+    writer.write(&path, text.as_bytes())?;
+    if writer == Writer::Disk {
+        println!("📦 Saved proof to: {}", path.display());
+    }
+    Ok(path)
+}
+
+/// A proof in snarkjs' proof.json layout: projective coordinates as decimal strings,
+/// G2 coordinates as [c0, c1] pairs
+fn snarkjs_proof_json(proof: &Proof<Bn254>) -> serde_json::Value {
+    let g1 = |p: &G1Affine| match p.infinity {
+        false => serde_json::json!([p.x.to_string(), p.y.to_string(), "1"]),
+        true => serde_json::json!(["0", "1", "0"]),
+    };
+    let g2 = |p: &G2Affine| match p.infinity {
+        false => serde_json::json!([
+            [p.x.c0.to_string(), p.x.c1.to_string()],
+            [p.y.c0.to_string(), p.y.c1.to_string()],
+            ["1", "0"],
+        ]),
+        true => serde_json::json!([["0", "0"], ["1", "0"], ["0", "0"]]),
+    };
+    serde_json::json!({
+        "pi_a": g1(&proof.a),
+        "pi_b": g2(&proof.b),
+        "pi_c": g1(&proof.c),
+        "protocol": "groth16",
+        "curve": "bn128",
+    })
+}
+
+fn main() -> Result<()> {
+    match run(Cli::parse()) {
+        Err(e) if is_reported_failure(&e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+        result => result,
+    }
+}
+
+/// Failures already explained to the user; `main` prints them and exits with status 1
+fn is_reported_failure(e: &anyhow::Error) -> bool {
+    e.is::<MissingArtifact>() || e.is::<BatchFailed>() || e.is::<ProofRejected>() || e.is::<KeyExists>()
+}
+
+/// Artefacts read by `Verify`
+#[derive(Debug, Clone, Copy)]
+enum Artifact {
+    Proof,
+    PublicInput,
+    VerifyingKey,
+    PreparedVerifyingKey,
+}
+
+impl Artifact {
+    fn describe(self) -> &'static str {
+        match self {
+            Artifact::Proof => "proof",
+            Artifact::PublicInput => "public input",
+            Artifact::VerifyingKey => "verifying key",
+            Artifact::PreparedVerifyingKey => "prepared verifying key",
+        }
+    }
+
+    fn hint(self) -> &'static str {
+        match self {
+            Artifact::VerifyingKey | Artifact::PreparedVerifyingKey => "run `zkcli prove` first to generate keys",
+            _ => "run `zkcli prove` first to generate a proof and public input",
+        }
+    }
+}
+
+/// A file `Verify` needs does not exist; printed as a short message instead of an error chain
+#[derive(Debug)]
+struct MissingArtifact {
+    artifact: Artifact,
+    path: PathBuf,
+}
+
+impl std::fmt::Display for MissingArtifact {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "❌ No {} found at {} — {}",
+            self.artifact.describe(),
+            self.path.display(),
+            self.artifact.hint()
+        )
+    }
+}
+
+impl std::error::Error for MissingArtifact {}
+
+/// `VerifyDir` found at least one pair that did not verify
+#[derive(Debug)]
+struct BatchFailed {
+    failed: usize,
+    total: usize,
+}
+
+impl std::fmt::Display for BatchFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "❌ {} of {} proof(s) failed verification", self.failed, self.total)
+    }
+}
+
+impl std::error::Error for BatchFailed {}
+
+/// A proving command would replace a verifying key that earlier proofs depend on
+#[derive(Debug)]
+struct KeyExists {
+    path: PathBuf,
+}
+
+impl std::fmt::Display for KeyExists {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "❌ {} already exists — proving runs a new setup, so replacing it breaks every proof and \
+             contract made with the current key; pass --force to replace it anyway",
+            self.path.display()
+        )
+    }
+}
+
+impl std::error::Error for KeyExists {}
+
+/// Refuse to let a proving command overwrite `verifying_key.bin` without `--force`,
+/// and warn when it is forced. A dry run writes nothing, so it is never refused.
+fn guard_verifying_key(dirs: &ArtifactDirs, force: bool, writer: Writer) -> Result<()> {
+    let path = dirs.keys.join("verifying_key.bin");
+    if writer == Writer::DryRun || !path.exists() {
+        return Ok(());
+    }
+    if !force {
+        return Err(KeyExists { path }.into());
+    }
+    println!("⚠️ Warning: replacing {}; proofs and contracts made with the old key will no longer verify", path.display());
+    Ok(())
+}
+
+/// `Verify` loaded everything, but the proof does not verify
+#[derive(Debug)]
+struct ProofRejected;
+
+impl std::fmt::Display for ProofRejected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "❌ Proof failed verification")
+    }
+}
+
+impl std::error::Error for ProofRejected {}
+
+/// Outcome of one pair in `VerifyDir`: `Ok(valid)` or why the pair could not be loaded
+struct PairResult {
+    name: String,
+    outcome: std::result::Result<bool, String>,
+}
+
+impl PairResult {
+    fn passed(&self) -> bool {
+        matches!(self.outcome, Ok(true))
+    }
+}
+
+fn verify_dir(dir: &Path, vk: &VerifyingKey<Bn254>) -> Result<Vec<PairResult>> {
+    let mut names = Vec::new();
+    for entry in std::fs::read_dir(dir).with_context(|| format!("reading directory {}", dir.display()))? {
+        let file_name = entry?.file_name();
+        if let Some(name) = file_name.to_str().and_then(|n| n.strip_suffix(".proof.bin")) {
+            names.push(name.to_string());
+        }
+    }
+    names.sort();
+
+    Ok(names
+        .into_iter()
+        .map(|name| {
+            let proof = load_proof(&dir.join(format!("{name}.proof.bin")));
+            let input = load_public_input(&dir.join(format!("{name}.input.bin")));
+            let outcome = match (proof, input) {
+                (Ok(proof), Ok(input)) => prover::verify_proof(&proof, input, vk).map_err(|e| e.to_string()),
+                (Err(e), _) => Err(format!("proof: {e}")),
+                (_, Err(e)) => Err(format!("input: {e}")),
+            };
+            PairResult { name, outcome }
+        })
+        .collect())
+}
+
+/// Export the constraint system and assignment of a sample instance of `circuit`
+fn dump_r1cs(circuit: &str) -> Result<R1csExport> {
+    let export = match circuit {
+        "mul" => r1cs_export_with_assignment(MulCircuit::instance(3, 4))?,
+        "sum" => r1cs_export_with_assignment(SumCircuit::instance(&[1, 2, 3]))?,
+        "factor" => r1cs_export_with_assignment(FactorCircuit::instance(5, 7))?,
+        other => anyhow::bail!("unknown circuit `{other}` (expected mul, sum or factor)"),
+    };
+    Ok(export)
+}
+
+/// Circuits `ProveJson` can build, with the witness fields each one needs
+const WITNESS_CIRCUITS: &[(&str, &[&str])] = &[
+    ("mul", &["a", "b"]),
+    ("parity", &["value"]),
+    ("range", &["value", "num_bits", "bit"]),
+];
+
+/// Read the fields `circuit` needs from a JSON witness file, in registry order.
+/// Every missing field is reported at once.
+fn read_witness(path: &Path, circuit: &str) -> Result<Vec<u64>> {
+    let Some((_, fields)) = WITNESS_CIRCUITS.iter().find(|(name, _)| *name == circuit) else {
+        let known: Vec<&str> = WITNESS_CIRCUITS.iter().map(|(name, _)| *name).collect();
+        anyhow::bail!("unknown circuit `{circuit}` (expected {})", known.join(", "));
+    };
+    let text = std::fs::read_to_string(path).with_context(|| format!("reading witness {}", path.display()))?;
+    let json: serde_json::Value = serde_json::from_str(&text).context("parsing witness JSON")?;
+    let object = json.as_object().context("witness must be a JSON object of named assignments")?;
+
+    let missing: Vec<&str> = fields.iter().copied().filter(|field| !object.contains_key(*field)).collect();
+    if !missing.is_empty() {
+        anyhow::bail!("witness for `{circuit}` is missing {}", missing.join(", "));
+    }
+    fields
+        .iter()
+        .map(|field| {
+            object[*field]
+                .as_u64()
+                .with_context(|| format!("witness field `{field}` is not an unsigned 64-bit integer"))
+        })
+        .collect()
+}
+
+/// Check, set up and prove `instance`, then save the proof, `public` and the verifying key
+fn prove_witness<C>(setup: C, instance: C, public: Fr, dirs: &ArtifactDirs, writer: Writer) -> Result<()>
+where
+    C: ConstraintSynthesizer<Fr> + Assignment + Clone,
+{
+    check_satisfied(instance.clone())?;
+    let (pk, _) = Groth16::<Bn254>::circuit_specific_setup(setup, &mut thread_rng())?;
+    let proof = prove_circuit(&pk, instance)?;
+    println!("🔍 {}", ProofSummary::new(&proof));
+
+    save_proof_with_writer(&proof, dirs, writer)?;
+    save_public_input_with_writer(&public, dirs, writer)?;
+    save_verifying_key_with_writer(&pk.vk, dirs, writer)?;
+    Ok(())
+}
+
+/// What `InspectKeyFile` learns from a verifying_key_bytes.rs file
+struct KeyFileInfo {
+    encoding: &'static str,
+    byte_len: usize,
+    fingerprint: [u8; 32],
+    gamma_abc_len: usize,
+}
+
+fn inspect_key_file(path: &Path) -> Result<KeyFileInfo> {
+    let src = std::fs::read_to_string(path)
+        .with_context(|| format!("reading key file {}", path.display()))?;
+    let bytes = parse_verifying_key_rs_source(&src)
+        .with_context(|| format!("parsing {}", path.display()))?;
+
+    let (vk, encoding) = match VerifyingKey::<Bn254>::deserialize_uncompressed(&bytes[..]) {
+        Ok(vk) => (vk, "uncompressed"),
+        Err(_) => (
+            VerifyingKey::<Bn254>::deserialize_compressed(&bytes[..])
+                .context("deserialising embedded verifying key")?,
+            "compressed",
+        ),
+    };
+
+    Ok(KeyFileInfo {
+        encoding,
+        byte_len: bytes.len(),
+        fingerprint: vk_fingerprint(&vk),
+        gamma_abc_len: vk.gamma_abc_g1.len(),
+    })
+}
+
+/// The summary `KeysInfo` prints for a verifying key
+fn describe_vk(path: &str, vk: &VerifyingKey<Bn254>) -> String {
+    format!(
+        "\n🔑  Verifying key ({}):\n\
+         • curve ........... BN254\n\
+         • fingerprint ..... 0x{}\n\
+         • public inputs ... {}\n\
+         • size ............ {} bytes uncompressed, {} compressed\n",
+        path,
+        hex_string(&vk_fingerprint(vk)),
+        vk.gamma_abc_g1.len().saturating_sub(1),
+        vk.uncompressed_size(),
+        vk.compressed_size(),
+    )
+}
+
+/// Key `Verify` checks against
+#[derive(Debug, Clone, Copy)]
+enum VerifierKey<'a> {
+    Plain(&'a Path),
+    Prepared(&'a Path),
+}
+
+/// What `Verify` reports: the result, and which input and key it was checked against
+#[derive(Debug, PartialEq)]
+struct VerifyOutcome {
+    valid: bool,
+    public_input: Fr,
+    vk_fingerprint: [u8; 32],
+}
+
+impl VerifyOutcome {
+    /// The `--json` output, e.g. `{"valid": true, "public_input": "12", "vk_fingerprint": "0x..."}`
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "valid": self.valid,
+            "public_input": self.public_input.to_string(),
+            "vk_fingerprint": format!("0x{}", hex_string(&self.vk_fingerprint)),
+        })
+    }
+}
+
+fn verify_files(proof_path: &Path, input_path: &Path, key: VerifierKey<'_>) -> Result<VerifyOutcome> {
+    let (valid, public_input, vk) = match key {
+        VerifierKey::Plain(vk_path) => {
+            let proof_bytes = load_artifact(proof_path, Artifact::Proof, read_versioned_proof)?;
+            let input_bytes = load_artifact(input_path, Artifact::PublicInput, read_versioned)?;
+            let vk_bytes    = load_artifact(vk_path, Artifact::VerifyingKey, read_versioned)?;
+            let valid = prepare_and_verify(&proof_bytes, &input_bytes, &vk_bytes).context("verifying proof")?;
+            // Both decoded cleanly inside `prepare_and_verify`, or it would have failed
+            let input = Fr::deserialize_uncompressed(&input_bytes[..]).context("decoding public input")?;
+            let vk = VerifyingKey::deserialize_uncompressed(&vk_bytes[..]).context("decoding verifying key")?;
+            (valid, input, vk)
+        }
+        VerifierKey::Prepared(pvk_path) => {
+            let proof = load_artifact(proof_path, Artifact::Proof, load_proof)?;
+            let input = load_artifact(input_path, Artifact::PublicInput, load_public_input)?;
+            let pvk   = load_artifact(pvk_path, Artifact::PreparedVerifyingKey, load_prepared_verifying_key)?;
+            (verify_with_prepared(&proof, input, &pvk).context("verifying proof")?, input, pvk.vk)
+        }
+    };
+    Ok(VerifyOutcome { valid, public_input, vk_fingerprint: vk_fingerprint(&vk) })
+}
+
+/// Integer-level sanity checks on `Prove` operands; the product is taken in u128 so it cannot overflow
+fn operand_warnings(a: u64, b: u64, c: u64) -> Vec<&'static str> {
+    let product = a as u128 * b as u128;
+    let mut warnings = Vec::new();
+    if product > u64::MAX as u128 {
+        warnings.push("⚠️ Warning: a * b overflows u64; the proof commits to the full product, which --c cannot express");
+    }
+    if product != c as u128 {
+        warnings.push("⚠️ Warning: you entered inputs that won't match the expected outputs!");
+    }
+    warnings
+}
+
+fn load_artifact<T>(
+    path: &Path,
+    artifact: Artifact,
+    load: fn(&Path) -> Result<T, ProverError>,
+) -> Result<T> {
+    match load(path) {
+        Ok(value) => Ok(value),
+        Err(ProverError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => {
+            Err(MissingArtifact { artifact, path: path.to_path_buf() }.into())
+        }
+        Err(e) => Err(anyhow::Error::new(e)
+            .context(format!("loading {} file {}", artifact.describe(), path.display()))),
     }
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let cli = Cli::parse();
+/// Set up and prove a * b = c, writing calldata to `calldata_path` and proof, public input
+/// and keys to `dirs`. Returns each artefact's label and path, in the order they are written.
+fn prove_mul(
+    a: u64,
+    b: u64,
+    c: u64,
+    calldata_path: PathBuf,
+    format: ProofFormat,
+    dirs: &ArtifactDirs,
+    writer: Writer,
+) -> Result<Vec<(&'static str, PathBuf)>> {
+    for warning in operand_warnings(a, b, c) {
+        println!("{warning}");
+    }
 
+    let setup_circuit = MulCircuit::setup_shape();
+    let prove_circuit = MulCircuit::instance(a, b);
+    let c_fr = prove_circuit.c.expect("instance assigns c"); // enforces property to handle user error
+
+    let mut rng = thread_rng();
+    let (params, _) = Groth16::<Bn254>::circuit_specific_setup(setup_circuit, &mut rng)?;
+    let proof = Groth16::<Bn254>::prove(&params, prove_circuit, &mut rng)?;
+    println!("🔍 {}", ProofSummary::new(&proof));
+
+    save_calldata_with_writer(&proof, &c_fr, &calldata_path, CalldataMode::Pvm, writer)?;
+    Ok(vec![
+        ("calldata", calldata_path),
+        ("proof", write_proof(&proof, dirs, format, writer)?),
+        ("public input", save_public_input_with_writer(&c_fr, dirs, writer)?),
+        ("verifying key", save_verifying_key_with_writer(&params.vk, dirs, writer)?),
+        ("prepared vk", save_prepared_verifying_key_with_writer(&prepare_verifying_key(&params.vk), dirs, writer)?),
+        ("vk byte array", export_verifying_key_to_rs_with_writer(&params.vk, dirs, writer)?),
+    ])
+}
+
+/// The artefact list printed after `Prove`, dot-padded to line up the paths
+fn artifact_report(artifacts: &[(&'static str, PathBuf)], writer: Writer) -> String {
+    let mut report = match writer {
+        Writer::Disk => String::from("✅ Wrote calldata, proof, public input, and verifying key.\n\n📂  Artefacts written:\n"),
+        Writer::DryRun => String::from("🧪 Dry run: no files were created.\n\n📂  Artefacts that would be written:\n"),
+    };
+    for (label, path) in artifacts {
+        report.push_str(&format!("• {} {:.<w$} {}\n", label, "", path.display(), w = 18 - label.len()));
+    }
+    report
+}
+
+fn run(cli: Cli) -> Result<()> {
+    let writer = if cli.dry_run { Writer::DryRun } else { Writer::Disk };
+    let dirs = cli.artifact_dirs();
     match &cli.command {
-        Commands::Prove { a, b, c, out } => {
-            let a_fr = Fr::from(*a);
-            let b_fr = Fr::from(*b);
-            let c_fr = a_fr * b_fr; // enforces property to handle user error
-        
-            if *a * *b != *c {
-                println!("⚠️ Warning: you entered inputs that won't match the expected outputs!");
-            }
+        Commands::Prove { a, b, c, out, format } => {
+            guard_verifying_key(&dirs, cli.force, writer)?;
+            let artifacts = prove_mul(*a, *b, *c, cli.output_dir.join(out), *format, &dirs, writer)?;
+            println!("{}", artifact_report(&artifacts, writer));
+        },
 
-            let setup_circuit = MulCircuit { a: None, b: None, c: None };
-            let prove_circuit = MulCircuit { a: Some(a_fr), b: Some(b_fr), c: Some(c_fr) };
-        
-            let mut rng = thread_rng();
-            let params = Groth16::<Bn254>::generate_random_parameters_with_reduction(setup_circuit, &mut rng)?;
-            let proof = Groth16::<Bn254>::create_random_proof_with_reduction(prove_circuit, &params, &mut rng)?;
-        
-            let calldata_path = PathBuf::from(out);                
-            let proof_path      = Path::new("../proofs/proof.bin");
-            let input_path      = Path::new("../proofs/public_input.bin");
-            let vk_bin_path     = Path::new("../keys/verifying_key.bin");
-            let vk_rs_path      = Path::new("../keys/verifying_key_bytes.rs");
-
-            std::fs::create_dir_all("../proofs")?;
-            std::fs::create_dir_all("../keys")?;
-        
-            save_calldata(&proof, &c_fr, out)?;
-            save_proof(&proof)?;
-            save_public_input(&c_fr)?;
-            save_verifying_key(&params.vk)?;
-            export_verifying_key_to_rs(&params.vk)?;
-        
-            println!("✅ Wrote calldata, proof, public input, and verifying key.");
-            println!(
-                "\n📂  Artefacts written:\n\
-                 • calldata .......... {}\n\
-                 • compressed proof .. {}\n\
-                 • public input ...... {}\n\
-                 • verifying key ..... {}\n\
-                 • vk byte array ..... {}\n",
-                calldata_path.display(),
-                proof_path.display(),
-                input_path.display(),
-                vk_bin_path.display(),
-                vk_rs_path.display(),
-            );
-        },        
-
-        Commands::Verify { proof, input, vk } => {        
-            // Load proof
-            let proof_path = PathBuf::from(proof);
-            let input_path = PathBuf::from(input);
-            let vk_path    = PathBuf::from(vk);
-            
-            println!("Proof: {:?}", proof);
-
-            let proof: Proof<Bn254> = {
-                let mut reader = BufReader::new(
-                    File::open(&proof_path)
-                        .with_context(|| format!("opening proof file {}", proof_path.display()))?
-                );
-                Proof::<Bn254>::deserialize_compressed(&mut reader)
-                    .context("deserialising Groth16 proof")?
+        Commands::ProveHash { secret_file } => {
+            guard_verifying_key(&dirs, cli.force, writer)?;
+            let text = match secret_file {
+                Some(path) => std::fs::read_to_string(path)
+                    .with_context(|| format!("reading secret from {}", path.display()))?,
+                None => {
+                    let mut text = String::new();
+                    std::io::stdin().read_to_string(&mut text).context("reading secret from stdin")?;
+                    text
+                }
             };
-        
-            let public_input: Fr = {
-                let mut reader = BufReader::new(
-                    File::open(&input_path)
-                        .with_context(|| format!("opening input file {}", input_path.display()))?
-                );
-                Fr::deserialize_uncompressed(&mut reader)
-                    .context("deserialising public input")?
+            let secret = parse_fr(&text).context("parsing secret")?;
+
+            let (proof, hash, pk) = prove_hash_preimage(secret)?;
+            println!("🔍 {}", ProofSummary::new(&proof));
+            println!("#️⃣  Poseidon hash (public input): {hash}");
+
+            save_proof_with_writer(&proof, &dirs, writer)?;
+            save_public_input_with_writer(&hash, &dirs, writer)?;
+            save_verifying_key_with_writer(&pk.vk, &dirs, writer)?;
+        }
+
+        Commands::ProveJson { witness, circuit } => {
+            guard_verifying_key(&dirs, cli.force, writer)?;
+            let values = read_witness(Path::new(witness), circuit)?;
+            match (circuit.as_str(), values.as_slice()) {
+                ("mul", &[a, b]) => {
+                    let instance = MulCircuit::instance(a, b);
+                    let c = instance.c.expect("instance assigns c");
+                    prove_witness(MulCircuit::setup_shape(), instance, c, &dirs, writer)?;
+                }
+                ("parity", &[value]) => {
+                    let instance = ParityCircuit::instance(value, 64);
+                    prove_witness(ParityCircuit::setup_shape(64), instance, Fr::from(value & 1), &dirs, writer)?;
+                }
+                ("range", &[value, num_bits, bit]) => {
+                    let (num_bits, bit) = (num_bits as usize, bit as usize);
+                    if num_bits > 64 || bit >= num_bits {
+                        anyhow::bail!("range needs bit < num_bits <= 64, got bit {bit} of {num_bits}");
+                    }
+                    let output = BitOutput::Bit(bit);
+                    let instance = RangeWithBitCircuit::instance(value, num_bits, output);
+                    let setup = RangeWithBitCircuit::setup_shape(num_bits, output);
+                    prove_witness(setup, instance, output.of(value), &dirs, writer)?;
+                }
+                _ => unreachable!("read_witness returns one value per registered field"),
+            }
+        }
+
+        Commands::Verify { proof, input, vk, pvk, json } => {
+            if !json {
+                println!("Proof: {:?}", proof);
+            }
+
+            let key = match (vk, pvk) {
+                (_, Some(pvk)) => VerifierKey::Prepared(Path::new(pvk)),
+                (Some(vk), None) => VerifierKey::Plain(Path::new(vk)),
+                (None, None) => unreachable!("clap requires --vk or --pvk"),
             };
-        
-            let vk: VerifyingKey<Bn254> = {
-                let mut reader = BufReader::new(
-                    File::open(&vk_path)
-                        .with_context(|| format!("opening verifying-key file {}", vk_path.display()))?
+            let outcome = verify_files(Path::new(proof), Path::new(input), key)?;
+
+            match json {
+                true => println!("{}", outcome.to_json()),
+                false => println!("✅ Verification result: {}", outcome.valid),
+            }
+            if !outcome.valid {
+                return Err(ProofRejected.into());
+            }
+        }
+
+        Commands::VerifyDir { dir, vk } => {
+            let vk = load_artifact(Path::new(vk), Artifact::VerifyingKey, load_verifying_key)?;
+            let results = verify_dir(Path::new(dir), &vk)?;
+
+            for result in &results {
+                match &result.outcome {
+                    Ok(true) => println!("  ✅ {}", result.name),
+                    Ok(false) => println!("  ❌ {} (proof rejected)", result.name),
+                    Err(reason) => println!("  ❌ {} ({})", result.name, reason),
+                }
+            }
+            let passed = results.iter().filter(|r| r.passed()).count();
+            let failed = results.len() - passed;
+            println!("\n{passed} passed, {failed} failed");
+
+            if failed > 0 {
+                return Err(BatchFailed { failed, total: results.len() }.into());
+            }
+        }
+
+        Commands::DumpR1cs { circuit, out } => {
+            let dump = dump_r1cs(circuit)?;
+            let out_path = cli.output_dir.join(out);
+            writer.write(&out_path, serde_json::to_string_pretty(&dump)?.as_bytes())?;
+            if writer == Writer::Disk {
+                println!(
+                    "📦 Saved R1CS of `{}` ({} constraints) to: {}",
+                    circuit,
+                    dump.constraints.len(),
+                    out_path.display(),
                 );
-                VerifyingKey::<Bn254>::deserialize_uncompressed(&mut reader)
-                    .context("deserialising verifying key")?
-            };
+            }
+        }
+
+        Commands::CompareVk { a, b } => {
+            let vk_a = load_artifact(Path::new(a), Artifact::VerifyingKey, load_verifying_key)?;
+            let vk_b = load_artifact(Path::new(b), Artifact::VerifyingKey, load_verifying_key)?;
 
-            // verify 
-            let pvk   = prepare_verifying_key(&vk);
-            let valid = Groth16::<Bn254>::verify_proof(&pvk, &proof, &[public_input])
-                .context("running pairing check")?;
-        
-            println!("✅ Verification result: {valid}");
+            let diff = vk_diff(&vk_a, &vk_b);
+            if diff.is_empty() {
+                println!("✅ Verifying keys are identical (fingerprint 0x{})", hex_string(&vk_fingerprint(&vk_a)));
+            } else {
+                println!("❌ Verifying keys differ in:");
+                for component in diff {
+                    println!("  • {component}");
+                }
+            }
+        }
+
+        Commands::ConvertProof { input, output, to } => {
+            let payload = load_artifact(Path::new(input), Artifact::Proof, read_versioned_proof)?;
+            let (proof, detected) = decode_proof(unframe_proof(&payload)?)
+                .with_context(|| format!("{input} is neither a compressed nor an uncompressed proof"))?;
+            println!("🔍 Detected {detected:?} proof ({} bytes)", payload.len());
+
+            let target = ProofEncoding::from(*to);
+            save_proof_as_with_writer(&proof, Path::new(output), target, writer)?;
+            if writer == Writer::Disk {
+                println!("📦 Saved {target:?} proof to: {output}");
+            }
+        }
+
+        Commands::MakeCalldata { proof, input, out } => {
+            let proof = load_artifact(Path::new(proof), Artifact::Proof, load_proof)?;
+            let input = load_artifact(Path::new(input), Artifact::PublicInput, load_public_input)?;
+            save_calldata_with_writer(&proof, &input, Path::new(out), CalldataMode::Pvm, writer)?;
+        }
+
+        Commands::InspectCalldata { path } => {
+            let calldata = std::fs::read(path).with_context(|| format!("reading calldata {path}"))?;
+            let (payload, mode) = calldata_payload(&calldata)?;
+            let input = extract_public_input_from_calldata(&calldata)?;
+            let proof = &payload[..PROOF_LEN];
+            println!(
+                "\n📨  Calldata ({}):\n\
+                 • framing ......... {:?} ({} bytes)\n\
+                 • public input .... {}\n\
+                 • proof A ......... 0x{}\n\
+                 • proof B ......... 0x{}\n\
+                 • proof C ......... 0x{}\n",
+                path,
+                mode,
+                calldata.len(),
+                input,
+                hex_string(&proof[..32]),
+                hex_string(&proof[32..96]),
+                hex_string(&proof[96..]),
+            );
+        }
+
+        Commands::InspectKeyFile { path } => {
+            let info = inspect_key_file(Path::new(path))?;
+            println!(
+                "\n🔑  Embedded verifying key ({}):\n\
+                 • encoding ........ {}\n\
+                 • size ............ {} bytes\n\
+                 • fingerprint ..... 0x{}\n\
+                 • gamma_abc_g1 .... {} ({} public input(s))\n",
+                path,
+                info.encoding,
+                info.byte_len,
+                hex_string(&info.fingerprint),
+                info.gamma_abc_len,
+                info.gamma_abc_len.saturating_sub(1),
+            );
+            if info.encoding != "uncompressed" {
+                println!("⚠️ The PVM contract deserializes the key uncompressed; this file will not load on-chain.");
+            }
+        }
+
+        Commands::KeysInfo { vk } => {
+            let key = load_artifact(Path::new(vk), Artifact::VerifyingKey, load_verifying_key)?;
+            println!("{}", describe_vk(vk, &key));
+        }
+
+        Commands::Sizes => {
+            println!("📏 Serialized sizes for a * b = c:\n{}", serialized_sizes()?);
         }
-        
     }
 
     Ok(())
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prover::utils::{save_proof, save_public_input, save_verifying_key};
+    use std::sync::Mutex;
+
+    // Tests touching ZKCLI_* variables must not interleave
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn test_prove_reads_operands_from_env() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        std::env::set_var("ZKCLI_A", "3");
+        std::env::set_var("ZKCLI_B", "4");
+        let out_dir = std::env::temp_dir().join("zkcli_env_prove");
+        let _ = std::fs::remove_dir_all(&out_dir);
+        let out = out_dir.join("calldata.bin");
+
+        let cli = Cli::try_parse_from([
+            "zkcli", "prove", "--c", "12", "--output-dir", out_dir.to_str().unwrap(),
+        ]);
+        std::env::remove_var("ZKCLI_A");
+        std::env::remove_var("ZKCLI_B");
+        let cli = cli.expect("operands should come from the environment");
+
+        match &cli.command {
+            Commands::Prove { a, b, .. } => assert_eq!((*a, *b), (3, 4)),
+            _ => panic!("expected the prove command"),
+        }
+        run(cli).expect("prove should succeed without --a/--b");
+        assert_eq!(std::fs::metadata(out).unwrap().len(), 164);
+    }
+
+    #[test]
+    fn test_verify_with_missing_vk_prints_hint() {
+        let (proof, c, _pk) = prover::generate_proof(3, 4).expect("proof generation failed");
+        let dir = std::env::temp_dir().join("zkcli_missing_vk");
+        let proof_path = save_proof(&proof, &dir).unwrap();
+        let input_path = save_public_input(&c, &dir).unwrap();
+
+        let cli = Cli::try_parse_from([
+            "zkcli",
+            "verify",
+            "--proof", proof_path.to_str().unwrap(),
+            "--input", input_path.to_str().unwrap(),
+            "--vk", dir.join("does_not_exist.bin").to_str().unwrap(),
+        ])
+        .unwrap();
+
+        let err = run(cli).expect_err("verify should fail without a verifying key");
+        assert!(err.is::<MissingArtifact>());
+        let message = err.to_string();
+        assert!(message.contains("No verifying key found"));
+        assert!(message.contains("run `zkcli prove` first to generate keys"));
+    }
+
+    #[test]
+    fn test_inspect_key_file_reports_source_fingerprint() {
+        let (_proof, _c, pk) = prover::generate_proof(3, 4).expect("proof generation failed");
+        let path = std::env::temp_dir().join("zkcli_inspect_verifying_key_bytes.rs");
+        std::fs::write(&path, prover::verifying_key_rs_source(&pk.vk).unwrap()).unwrap();
+
+        let info = inspect_key_file(&path).expect("inspection failed");
+        assert_eq!(info.fingerprint, vk_fingerprint(&pk.vk));
+        assert_eq!(info.encoding, "uncompressed");
+        assert_eq!(info.gamma_abc_len, 2);
+    }
+
+    #[test]
+    fn test_keys_info_reports_the_circuits_public_input_count() {
+        use prover::circuit::{PublicInputsCircuit, RootRelation};
+
+        let (_proof, _c, pk) = prover::generate_proof(3, 4).expect("proof generation failed");
+        let summary = describe_vk("mul.bin", &pk.vk);
+        assert!(summary.contains("public inputs ... 1\n"), "unexpected summary: {summary}");
+        assert!(summary.contains(&hex_string(&vk_fingerprint(&pk.vk))));
+
+        let (_pk, vk) = prover::setup_circuit_specific(PublicInputsCircuit::setup_shape(3, RootRelation { x: None }))
+            .expect("setup failed");
+        assert!(describe_vk("inputs.bin", &vk).contains("public inputs ... 3\n"));
+
+        let dir = std::env::temp_dir().join("zkcli_keys_info");
+        let vk_path = save_verifying_key(&vk, &dir).unwrap();
+        let cli = Cli::try_parse_from(["zkcli", "keys-info", "--vk", vk_path.to_str().unwrap()]).unwrap();
+        run(cli).expect("keys-info failed");
+    }
+
+    #[test]
+    fn test_prove_writes_artifacts_under_output_dir() {
+        let out_dir = std::env::temp_dir().join("zkcli_output_dir");
+        let _ = std::fs::remove_dir_all(&out_dir);
+
+        let cli = Cli::try_parse_from([
+            "zkcli", "prove", "--a", "3", "--b", "4", "--c", "12",
+            "--output-dir", out_dir.to_str().unwrap(),
+        ])
+        .unwrap();
+        run(cli).expect("prove failed");
+
+        assert_eq!(std::fs::metadata(out_dir.join("calldata.bin")).unwrap().len(), 164);
+        for artifact in [
+            "proofs/proof.bin",
+            "proofs/public_input.bin",
+            "keys/verifying_key.bin",
+            "keys/verifying_key_bytes.rs",
+        ] {
+            assert!(out_dir.join(artifact).exists(), "missing {}", artifact);
+        }
+    }
+
+    #[test]
+    fn test_second_prove_needs_force_to_replace_the_verifying_key() {
+        let out_dir = std::env::temp_dir().join("zkcli_force");
+        let _ = std::fs::remove_dir_all(&out_dir);
+        let prove = |extra: &[&str]| {
+            let mut args = vec!["zkcli", "prove", "--a", "3", "--b", "4", "--c", "12", "--output-dir", out_dir.to_str().unwrap()];
+            args.extend_from_slice(extra);
+            run(Cli::try_parse_from(args).unwrap())
+        };
+        let vk_path = out_dir.join("keys/verifying_key.bin");
+
+        prove(&[]).expect("first prove failed");
+        let first_vk = std::fs::read(&vk_path).unwrap();
+
+        let err = prove(&[]).expect_err("second prove must not replace the key");
+        assert!(err.is::<KeyExists>());
+        assert!(err.to_string().contains("--force"));
+        assert_eq!(std::fs::read(&vk_path).unwrap(), first_vk, "refused prove touched the key");
+
+        prove(&["--dry-run"]).expect("dry run writes nothing, so it is not refused");
+        prove(&["--force"]).expect("forced prove failed");
+        assert_ne!(std::fs::read(&vk_path).unwrap(), first_vk);
+    }
+
+    #[test]
+    fn test_keys_and_proofs_dirs_split_the_artifacts() {
+        let root = std::env::temp_dir().join("zkcli_split_dirs");
+        let _ = std::fs::remove_dir_all(&root);
+        let (out_dir, keys_dir, proofs_dir) = (root.join("out"), root.join("secure"), root.join("work"));
+
+        let cli = Cli::try_parse_from([
+            "zkcli", "prove", "--a", "3", "--b", "4", "--c", "12",
+            "--output-dir", out_dir.to_str().unwrap(),
+            "--keys-dir", keys_dir.to_str().unwrap(),
+            "--proofs-dir", proofs_dir.to_str().unwrap(),
+        ])
+        .unwrap();
+        run(cli).expect("prove failed");
+
+        assert!(out_dir.join("calldata.bin").exists());
+        for key in ["verifying_key.bin", "prepared_verifying_key.bin", "verifying_key_bytes.rs"] {
+            assert!(keys_dir.join(key).exists(), "missing key {}", key);
+        }
+        for proof in ["proof.bin", "public_input.bin"] {
+            assert!(proofs_dir.join(proof).exists(), "missing {}", proof);
+        }
+        assert!(!out_dir.join(KEYS_DIR).exists() && !out_dir.join(PROOFS_DIR).exists());
+    }
+
+    #[test]
+    fn test_dry_run_prove_creates_no_files_and_lists_artifacts() {
+        let out_dir = std::env::temp_dir().join("zkcli_dry_run");
+        let _ = std::fs::remove_dir_all(&out_dir);
+
+        let cli = Cli::try_parse_from([
+            "zkcli", "--dry-run", "prove", "--a", "3", "--b", "4", "--c", "12",
+            "--output-dir", out_dir.to_str().unwrap(),
+        ])
+        .unwrap();
+        assert!(cli.dry_run);
+        run(cli).expect("dry-run prove failed");
+        assert!(!out_dir.exists(), "dry run created {}", out_dir.display());
+
+        let dirs = ArtifactDirs::under(&out_dir);
+        let artifacts = prove_mul(3, 4, 12, out_dir.join("calldata.bin"), ProofFormat::Bin, &dirs, Writer::DryRun)
+            .expect("dry-run prove failed");
+        let report = artifact_report(&artifacts, Writer::DryRun);
+        assert!(report.contains("would be written"));
+        for artifact in [
+            "calldata.bin",
+            "proofs/proof.bin",
+            "proofs/public_input.bin",
+            "keys/verifying_key.bin",
+            "keys/prepared_verifying_key.bin",
+            "keys/verifying_key_bytes.rs",
+        ] {
+            assert!(report.contains(&out_dir.join(artifact).display().to_string()), "missing {}", artifact);
+        }
+        assert!(!out_dir.exists());
+    }
+
+    #[test]
+    fn test_prove_warns_on_u64_overflow_and_keeps_field_product() {
+        let (a, b) = (5_000_000_000u64, 5_000_000_000u64);
+        let warnings = operand_warnings(a, b, a.wrapping_mul(b));
+        assert!(warnings.iter().any(|w| w.contains("overflows u64")));
+        assert!(operand_warnings(3, 4, 12).is_empty());
+
+        let out_dir = std::env::temp_dir().join("zkcli_overflow");
+        let _ = std::fs::remove_dir_all(&out_dir);
+        let cli = Cli::try_parse_from([
+            "zkcli", "prove", "--a", &a.to_string(), "--b", &b.to_string(), "--c", "0",
+            "--output-dir", out_dir.to_str().unwrap(),
+        ])
+        .unwrap();
+        run(cli).expect("prove failed");
+
+        let c = load_public_input(&out_dir.join("proofs/public_input.bin")).unwrap();
+        assert_eq!(c, Fr::from(a as u128 * b as u128));
+        let proof = load_proof(&out_dir.join("proofs/proof.bin")).unwrap();
+        let vk = load_verifying_key(&out_dir.join("keys/verifying_key.bin")).unwrap();
+        assert!(prover::verify_proof(&proof, c, &vk).unwrap());
+    }
+
+    #[test]
+    fn test_verify_dir_counts_mixed_pairs() {
+        let (proof, c, pk) = prover::generate_proof(3, 4).expect("proof generation failed");
+        let scratch = std::env::temp_dir().join("zkcli_verify_dir_scratch");
+        let dir = std::env::temp_dir().join("zkcli_verify_dir");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let proof_path = save_proof(&proof, &scratch).unwrap();
+        let input_path = save_public_input(&c, &scratch).unwrap();
+        let vk_path = save_verifying_key(&pk.vk, &scratch).unwrap();
+        std::fs::copy(&proof_path, dir.join("good.proof.bin")).unwrap();
+        std::fs::copy(&input_path, dir.join("good.input.bin")).unwrap();
+
+        std::fs::copy(&proof_path, dir.join("wrong_input.proof.bin")).unwrap();
+        let wrong_input = save_public_input(&Fr::from(13u64), &scratch).unwrap();
+        std::fs::copy(&wrong_input, dir.join("wrong_input.input.bin")).unwrap();
+
+        std::fs::copy(&proof_path, dir.join("orphan.proof.bin")).unwrap();
+
+        let results = verify_dir(&dir, &pk.vk).unwrap();
+        let passed: Vec<&str> = results.iter().filter(|r| r.passed()).map(|r| r.name.as_str()).collect();
+        assert_eq!(results.len(), 3);
+        assert_eq!(passed, ["good"]);
+
+        let cli = Cli::try_parse_from([
+            "zkcli", "verify-dir",
+            "--dir", dir.to_str().unwrap(),
+            "--vk", vk_path.to_str().unwrap(),
+        ])
+        .unwrap();
+        let err = run(cli).expect_err("a failing pair should fail the command");
+        assert!(is_reported_failure(&err));
+        let failed = err.downcast_ref::<BatchFailed>().unwrap();
+        assert_eq!((failed.failed, failed.total), (2, 3));
+    }
+
+    #[test]
+    fn test_dump_r1cs_of_mul_circuit() {
+        let out_dir = std::env::temp_dir().join("zkcli_dump_r1cs");
+        let cli = Cli::try_parse_from([
+            "zkcli", "dump-r1cs", "--circuit", "mul", "--output-dir", out_dir.to_str().unwrap(),
+        ])
+        .unwrap();
+        run(cli).expect("dump failed");
+
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(out_dir.join("r1cs.json")).unwrap()).unwrap();
+        // Same schema as `prover::r1cs::export_r1cs_json`, plus the instance's assignment
+        let library = r1cs_export_with_assignment(MulCircuit::instance(3, 4)).unwrap();
+        assert_eq!(json, serde_json::to_value(library).unwrap());
+        assert_eq!(json["constraints"][0]["a"], serde_json::json!([[2, "1"]]));
+        assert_eq!(json["instance_assignment"], serde_json::json!(["1", "12"]));
+        assert_eq!(json["witness_assignment"], serde_json::json!(["3", "4", "12"]));
+
+        assert!(dump_r1cs("nope").is_err());
+    }
+
+    #[test]
+    fn test_verify_with_prepared_key_matches_plain_key() {
+        let out_dir = std::env::temp_dir().join("zkcli_verify_pvk");
+        let _ = std::fs::remove_dir_all(&out_dir);
+        let cli = Cli::try_parse_from([
+            "zkcli", "prove", "--a", "3", "--b", "4", "--c", "12", "--output-dir", out_dir.to_str().unwrap(),
+        ])
+        .unwrap();
+        run(cli).expect("prove failed");
+
+        let proof = out_dir.join("proofs/proof.bin");
+        let input = out_dir.join("proofs/public_input.bin");
+        let vk = out_dir.join("keys/verifying_key.bin");
+        let pvk = out_dir.join("keys/prepared_verifying_key.bin");
+
+        let start = std::time::Instant::now();
+        let plain = verify_files(&proof, &input, VerifierKey::Plain(&vk)).unwrap();
+        let plain_time = start.elapsed();
+        let start = std::time::Instant::now();
+        let prepared = verify_files(&proof, &input, VerifierKey::Prepared(&pvk)).unwrap();
+        let prepared_time = start.elapsed();
+        println!("--vk: {plain_time:?}, --pvk: {prepared_time:?}");
+
+        assert!(plain.valid);
+        assert_eq!(plain, prepared);
+
+        let both = Cli::try_parse_from([
+            "zkcli", "verify", "--proof", "p", "--input", "i", "--vk", "v", "--pvk", "pv",
+        ]);
+        assert!(both.is_err(), "--vk and --pvk are mutually exclusive");
+        let neither = Cli::try_parse_from(["zkcli", "verify", "--proof", "p", "--input", "i"]);
+        assert!(neither.is_err(), "one of --vk or --pvk is required");
+    }
+
+    #[test]
+    fn test_verify_json_reports_result_input_and_key() {
+        let (proof, c, pk) = prover::generate_proof(3, 4).expect("proof generation failed");
+        let dir = std::env::temp_dir().join("zkcli_verify_json");
+        let proof_path = save_proof(&proof, &dir).unwrap();
+        let input_path = save_public_input(&c, &dir).unwrap();
+        let vk_path = save_verifying_key(&pk.vk, &dir).unwrap();
+
+        let outcome = verify_files(&proof_path, &input_path, VerifierKey::Plain(&vk_path)).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&outcome.to_json().to_string()).unwrap();
+        assert_eq!(json["valid"], true);
+        assert_eq!(json["public_input"], "12");
+        assert_eq!(json["vk_fingerprint"], format!("0x{}", hex_string(&vk_fingerprint(&pk.vk))));
+
+        // A rejected proof still prints its JSON, then fails so the exit status is 1
+        let wrong_input = save_public_input(&(c + Fr::from(1u64)), &dir.join("wrong")).unwrap();
+        let cli = Cli::try_parse_from([
+            "zkcli", "verify", "--json",
+            "--proof", proof_path.to_str().unwrap(),
+            "--input", wrong_input.to_str().unwrap(),
+            "--vk", vk_path.to_str().unwrap(),
+        ])
+        .unwrap();
+        let err = run(cli).expect_err("a rejected proof must fail");
+        assert!(err.is::<ProofRejected>());
+        assert!(is_reported_failure(&err));
+    }
+
+    #[test]
+    fn test_convert_proof_round_trip_restores_original_bytes() {
+        let (proof, _c, _pk) = prover::generate_proof(3, 4).expect("proof generation failed");
+        let dir = std::env::temp_dir().join("zkcli_convert_proof");
+        let original = save_proof(&proof, &dir).unwrap();
+        let uncompressed = dir.join("proof.uncompressed.bin");
+        let compressed = dir.join("proof.compressed.bin");
+
+        for (input, output, to) in [(&original, &uncompressed, "uncompressed"), (&uncompressed, &compressed, "compressed")] {
+            let cli = Cli::try_parse_from([
+                "zkcli", "convert-proof",
+                "--input", input.to_str().unwrap(),
+                "--output", output.to_str().unwrap(),
+                "--to", to,
+            ])
+            .unwrap();
+            run(cli).expect("conversion failed");
+        }
+
+        let (_, encoding) = decode_proof(unframe_proof(&read_versioned(&uncompressed).unwrap()).unwrap()).unwrap();
+        assert_eq!(encoding, ProofEncoding::Uncompressed);
+        assert_eq!(std::fs::read(&compressed).unwrap(), std::fs::read(&original).unwrap());
+    }
+
+    #[test]
+    fn test_make_calldata_matches_prove_byte_for_byte() {
+        let out_dir = std::env::temp_dir().join("zkcli_make_calldata");
+        let _ = std::fs::remove_dir_all(&out_dir);
+        let cli = Cli::try_parse_from([
+            "zkcli", "prove", "--a", "3", "--b", "4", "--c", "12",
+            "--output-dir", out_dir.to_str().unwrap(),
+        ])
+        .unwrap();
+        run(cli).expect("prove failed");
+
+        let original = out_dir.join("calldata.bin");
+        let expected = std::fs::read(&original).unwrap();
+        std::fs::remove_file(&original).unwrap();
+
+        let cli = Cli::try_parse_from([
+            "zkcli", "make-calldata",
+            "--proof", out_dir.join("proofs/proof.bin").to_str().unwrap(),
+            "--input", out_dir.join("proofs/public_input.bin").to_str().unwrap(),
+            "--out", original.to_str().unwrap(),
+        ])
+        .unwrap();
+        run(cli).expect("make-calldata failed");
+
+        assert_eq!(std::fs::read(&original).unwrap(), expected);
+    }
+
+    // Run `prove --format <format>` and return the proof file it wrote and the verifying key
+    fn prove_with_format(format: &str) -> (PathBuf, VerifyingKey<Bn254>) {
+        let dir = std::env::temp_dir().join(format!("zkcli_prove_format_{format}"));
+        let _ = std::fs::remove_dir_all(&dir);
+        let cli = Cli::try_parse_from([
+            "zkcli", "prove", "--a", "3", "--b", "4", "--c", "12",
+            "--format", format,
+            "--output-dir", dir.to_str().unwrap(),
+        ])
+        .unwrap();
+        run(cli).expect("prove failed");
+
+        let vk = load_verifying_key(&dir.join("keys").join("verifying_key.bin")).unwrap();
+        (dir.join(PROOFS_DIR).join(format!("proof.{format}")), vk)
+    }
+
+    fn assert_verifies(proof: &Proof<Bn254>, vk: &VerifyingKey<Bn254>) {
+        assert!(prover::verify_proof(proof, Fr::from(12u64), vk).expect("verification failed"));
+    }
+
+    #[test]
+    fn test_prove_format_bin_is_the_versioned_proof() {
+        let (path, vk) = prove_with_format("bin");
+        assert_verifies(&load_proof(&path).expect("bin proof does not load"), &vk);
+    }
+
+    #[test]
+    fn test_prove_format_hex_is_prefixed_compressed_proof() {
+        let (path, vk) = prove_with_format("hex");
+        let text = std::fs::read_to_string(&path).unwrap();
+        let digits = text.trim_end().strip_prefix("0x").expect("hex proof is not 0x-prefixed");
+        let bytes: Vec<u8> = (0..digits.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).unwrap())
+            .collect();
+
+        let (proof, encoding) = decode_proof(&bytes).expect("hex proof does not decode");
+        assert_eq!(encoding, ProofEncoding::Compressed);
+        assert_verifies(&proof, &vk);
+    }
+
+    #[test]
+    fn test_prove_format_json_uses_snarkjs_layout() {
+        use ark_bn254::{Fq, Fq2};
+        use std::str::FromStr;
+
+        let (path, vk) = prove_with_format("json");
+        let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(json["protocol"], "groth16");
+        assert_eq!(json["curve"], "bn128");
+
+        let fq = |v: &serde_json::Value| Fq::from_str(v.as_str().unwrap()).expect("coordinate is not decimal");
+        let fq2 = |v: &serde_json::Value| Fq2::new(fq(&v[0]), fq(&v[1]));
+        let g1 = |v: &serde_json::Value| {
+            assert_eq!(v[2], "1");
+            G1Affine::new(fq(&v[0]), fq(&v[1]))
+        };
+        let proof = Proof {
+            a: g1(&json["pi_a"]),
+            b: G2Affine::new(fq2(&json["pi_b"][0]), fq2(&json["pi_b"][1])),
+            c: g1(&json["pi_c"]),
+        };
+        assert_verifies(&proof, &vk);
+    }
+
+    fn prove_json(name: &str, circuit: &str, witness: &str) -> (PathBuf, Result<()>) {
+        let dir = std::env::temp_dir().join(format!("zkcli_prove_json_{name}"));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let witness_path = dir.join("witness.json");
+        std::fs::write(&witness_path, witness).unwrap();
+
+        let cli = Cli::try_parse_from([
+            "zkcli", "prove-json",
+            "--witness", witness_path.to_str().unwrap(),
+            "--circuit", circuit,
+            "--output-dir", dir.to_str().unwrap(),
+        ])
+        .unwrap();
+        (dir, run(cli))
+    }
+
+    #[test]
+    fn test_prove_json_proves_registered_circuits() {
+        for (circuit, witness, public) in [
+            ("mul", r#"{"a": 3, "b": 4}"#, 12u64),
+            ("parity", r#"{"value": 7}"#, 1),
+            ("range", r#"{"value": 5, "num_bits": 8, "bit": 1}"#, 0),
+        ] {
+            let (dir, result) = prove_json(circuit, circuit, witness);
+            result.unwrap_or_else(|e| panic!("{circuit} witness failed: {e}"));
+
+            let proof = load_proof(&dir.join("proofs").join("proof.bin")).unwrap();
+            let input = load_public_input(&dir.join("proofs").join("public_input.bin")).unwrap();
+            let vk = load_verifying_key(&dir.join("keys").join("verifying_key.bin")).unwrap();
+            assert_eq!(input, Fr::from(public));
+            assert!(prover::verify_proof(&proof, input, &vk).unwrap(), "{circuit} proof does not verify");
+        }
+    }
+
+    #[test]
+    fn test_prove_json_lists_every_missing_field() {
+        let (_dir, result) = prove_json("incomplete", "range", r#"{"num_bits": 8}"#);
+        let err = result.expect_err("incomplete witness should be rejected").to_string();
+        assert!(err.contains("missing value, bit"), "unexpected error: {err}");
+
+        let (_dir, result) = prove_json("not_a_number", "mul", r#"{"a": 3, "b": "four"}"#);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_prove_without_flag_or_env_errors() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        let err = Cli::try_parse_from(["zkcli", "prove", "--a", "3", "--c", "12"]).err();
+        assert!(err.is_some(), "missing --b and ZKCLI_B should be rejected");
+    }
+}
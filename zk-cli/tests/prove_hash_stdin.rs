@@ -0,0 +1,29 @@
+// Runs the zkcli binary with the secret piped through stdin.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use prover::poseidon::poseidon_sponge_hash;
+use prover::utils::{load_proof, load_public_input, load_verifying_key};
+
+#[test]
+fn secret_piped_through_stdin_proves_its_hash() {
+    let out_dir = std::env::temp_dir().join("zkcli_prove_hash_stdin");
+    let _ = std::fs::remove_dir_all(&out_dir);
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_zkcli"))
+        .args(["prove-hash", "--output-dir", out_dir.to_str().unwrap()])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .expect("failed to start zkcli");
+    child.stdin.take().unwrap().write_all(b"123456789\n").unwrap();
+    assert!(child.wait().unwrap().success());
+
+    let hash = load_public_input(&out_dir.join("proofs/public_input.bin")).unwrap();
+    assert_eq!(hash, poseidon_sponge_hash(&[ark_bn254::Fr::from(123456789u64)]));
+
+    let proof = load_proof(&out_dir.join("proofs/proof.bin")).unwrap();
+    let vk = load_verifying_key(&out_dir.join("keys/verifying_key.bin")).unwrap();
+    assert!(prover::verify_proof(&proof, hash, &vk).unwrap());
+}